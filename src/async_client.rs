@@ -0,0 +1,446 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use serde::de::DeserializeOwned;
+use url::Url;
+use crate::endpoints::alerts::{Alert, AlertCollection, AlertCount, AlertFilter, AlertTypes, AsyncAlertIter};
+use crate::endpoints::aviation::{Cwa, CwaCollection, Cwsu, Sigmet, SigmetCollection};
+use crate::endpoints::glossary::Glossary;
+use crate::endpoints::gridpoints::{Forecast, ForecastUnits, Gridpoint, GridpointStations};
+use crate::endpoints::icons::{IconCollection, IconCondition, IconSet, IconSize, TimeOfDay};
+use crate::endpoints::offices::{Office, OfficeHeadline, OfficeHeadlineCollection};
+use crate::endpoints::points::{Point, PointCache};
+use crate::endpoints::products::{Product, ProductCollection, ProductFilter, ProductLocations, ProductTypeCollection};
+use crate::endpoints::radar::{parse_listing, RadarProfiler, RadarQueueCollection, RadarQueueFilter, RadarServer, RadarServerCollection, RadarStation, RadarStationAlarmCollection, RadarStationCollection, RadarType, RemoteFile};
+use crate::endpoints::stations::{Observation, ObservationCollection, ObservationStation, ObservationStationCollection, TafCollection};
+use crate::endpoints::zones::{Zone, ZoneCollection, ZoneFilter, ZoneForecast, ZoneType};
+use crate::request::{self, ApiRequest, ApiResponse, Pipeline};
+use crate::cache::{CacheStats, CacheStatus, CacheStore, Cached, ResponseCache};
+use crate::rate_limit::{RateLimit, RateLimiter};
+use crate::retry::RetryPolicy;
+use crate::transport::{AsyncTransport, ReqwestAsyncTransport};
+use crate::Result;
+
+
+/// Client used to access the NWS API endpoints without blocking.
+/// 
+/// Has the same methods as [`NwsClient`](crate::NwsClient), except that [`alerts_iter`](Self::alerts_iter) returns
+/// an [`AsyncAlertIter`] to be polled with `next().await`. Requires a Tokio runtime.
+#[derive(Clone, Debug)]
+pub struct NwsAsyncClient {
+    transport: Arc<dyn AsyncTransport>,
+    pipeline: Pipeline,
+    point_cache: Arc<PointCache>,
+}
+impl NwsAsyncClient {
+    pub fn new(user_agent: &str) -> Result<NwsAsyncClient> {
+        Ok(NwsAsyncClient {
+            transport: Arc::new(ReqwestAsyncTransport::new(user_agent)?),
+            pipeline: Pipeline::default(),
+            point_cache: Arc::default(),
+        })
+    }
+    
+    /// Sends every request through `transport` instead of `reqwest`.
+    pub fn with_transport(mut self, transport: impl AsyncTransport + 'static) -> Self {
+        self.transport = Arc::new(transport);
+        self
+    }
+    
+    /// Sends API requests to `base_url` instead of [`ROOT`](crate::ROOT), as with [`NwsClient::with_base_url`](crate::NwsClient::with_base_url).
+    pub fn with_base_url(mut self, base_url: impl AsRef<str>) -> Self {
+        self.pipeline.base_url = base_url.as_ref().trim_end_matches('/').to_owned();
+        self
+    }
+    
+    /// Crawls MRMS radar directories on `mrms_url` instead of [`MRMS_ROOT`](crate::MRMS_ROOT).
+    pub fn with_mrms_url(mut self, mrms_url: impl AsRef<str>) -> Self {
+        self.pipeline.mrms_url = mrms_url.as_ref().trim_end_matches('/').to_owned();
+        self
+    }
+    
+    /// Replaces the default [`RetryPolicy`], which applies to every request including the radar crawl. Use
+    /// [`RetryPolicy::none`] to make a single attempt.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.pipeline.retry_policy = retry_policy;
+        self
+    }
+    
+    /// Limits API requests, including retries, to `limit`. The budget is shared by all clones of this client.
+    pub fn with_rate_limit(mut self, limit: RateLimit) -> Self {
+        self.pipeline.rate_limiter = Some(Arc::new(RateLimiter::new(limit)));
+        self
+    }
+    
+    /// Limits requests to the MRMS host crawled by `radar`, separately from the API budget.
+    pub fn with_mrms_rate_limit(mut self, limit: RateLimit) -> Self {
+        self.pipeline.mrms_rate_limiter = Some(Arc::new(RateLimiter::new(limit)));
+        self
+    }
+    
+    /// Caches responses in `store`, e.g. a [`MemoryCache`](crate::cache::MemoryCache) or [`DiskCache`](crate::cache::DiskCache),
+    /// following the server's freshness headers. The cache is shared by all clones of this client.
+    pub fn with_cache(mut self, store: impl CacheStore + 'static) -> Self {
+        self.pipeline.cache = Some(Arc::new(ResponseCache::new(store)));
+        self
+    }
+    
+    /// Keeps the `capacity` most recently used `/points` lookups made by the `*_at` helpers, instead of 1024. The
+    /// lookups are shared by all clones of this client made afterwards.
+    pub fn with_point_cache_capacity(mut self, capacity: usize) -> Self {
+        self.point_cache = Arc::new(PointCache::new(capacity));
+        self
+    }
+    
+    /// Cache outcomes so far. All zero if no cache is configured.
+    pub fn cache_stats(&self) -> CacheStats {
+        self.pipeline.cache.as_ref().map(|cache| cache.stats()).unwrap_or_default()
+    }
+    
+    /// Where the response to the last successful request made through this handle came from, e.g. to tell whether
+    /// [`gridpoints`](Self::gridpoints) returned cached data. `None` before the first request and after a failed one.
+    /// 
+    /// Clones keep their own status. When one handle runs requests concurrently, this is the one that finished last.
+    pub fn last_cache_status(&self) -> Option<CacheStatus> {
+        self.pipeline.last_cache_status.get()
+    }
+    
+    /// Follows any API URL, such as [`Point::forecast_grid_data`], reporting whether the response came from the cache.
+    pub async fn fetch_url_cached<T: DeserializeOwned>(&self, url: &Url) -> Result<Cached<T>> {
+        self.execute_cached(&ApiRequest::url(url), ApiResponse::json).await
+    }
+    
+    pub async fn alerts(&self, start: Option<DateTime<Utc>>, end: Option<DateTime<Utc>>, filter: &AlertFilter, cursor: Option<&str>) -> Result<AlertCollection> {
+        self.fetch(&request::alerts(start, end, filter, cursor)).await
+    }
+    
+    /// Iterates over every alert matching the query, requesting further pages from `/alerts` as needed.
+    pub fn alerts_iter(&self, start: Option<DateTime<Utc>>, end: Option<DateTime<Utc>>, filter: &AlertFilter) -> AsyncAlertIter<'_> {
+        AsyncAlertIter::new(self, request::alerts(start, end, filter, None))
+    }
+    
+    /// Every alert matching the query, from all pages of `/alerts`.
+    pub async fn alerts_all(&self, start: Option<DateTime<Utc>>, end: Option<DateTime<Utc>>, filter: &AlertFilter) -> Result<Vec<Alert>> {
+        let mut alerts = self.alerts_iter(start, end, filter);
+        let mut all = Vec::new();
+        while let Some(alert) = alerts.next().await {
+            all.push(alert?);
+        }
+        
+        Ok(all)
+    }
+    
+    pub async fn alerts_active(&self, filter: &AlertFilter) -> Result<AlertCollection> {
+        self.fetch(&request::alerts_active(filter)).await
+    }
+    
+    pub async fn alerts_active_count(&self) -> Result<AlertCount> {
+        self.fetch(&request::alerts_active_count()).await
+    }
+    
+    pub async fn alerts_active_zone(&self, zone_id: &str) -> Result<AlertCollection> {
+        self.fetch(&request::alerts_active_zone(zone_id)).await
+    }
+    
+    pub async fn alerts_active_area(&self, area: &str) -> Result<AlertCollection> {
+        self.fetch(&request::alerts_active_area(area)).await
+    }
+    
+    pub async fn alerts_active_region(&self, region: &str) -> Result<AlertCollection> {
+        self.fetch(&request::alerts_active_region(region)).await
+    }
+    
+    pub async fn alerts_types(&self) -> Result<AlertTypes> {
+        self.fetch(&request::alerts_types()).await
+    }
+    
+    pub async fn alerts_id(&self, id: &str) -> Result<Alert> {
+        self.fetch(&request::alerts_id(id)).await
+    }
+    
+    pub async fn aviation_cwsus(&self, id: &str) -> Result<Cwsu> {
+        self.fetch(&request::aviation_cwsus(id)).await
+    }
+    
+    pub async fn aviation_cwsus_cwas(&self, id: &str) -> Result<CwaCollection> {
+        self.fetch(&request::aviation_cwsus_cwas(id)).await
+    }
+    
+    pub async fn aviation_cwsus_cwas_id(&self, id: &str, date: NaiveDate, sequence: usize) -> Result<Cwa> {
+        self.fetch(&request::aviation_cwsus_cwas_id(id, date, sequence)).await
+    }
+    
+    pub async fn aviation_sigmets(&self, start: Option<DateTime<Utc>>, end: Option<DateTime<Utc>>, date: Option<NaiveDate>, atsu: Option<&str>, sequence: Option<&str>) -> Result<SigmetCollection> {
+        self.fetch(&request::aviation_sigmets(start, end, date, atsu, sequence)).await
+    }
+    
+    pub async fn aviation_sigmets_atsu(&self, atsu: &str) -> Result<SigmetCollection> {
+        self.fetch(&request::aviation_sigmets_atsu(atsu)).await
+    }
+    
+    pub async fn aviation_sigmets_atsu_date(&self, atsu: &str, date: NaiveDate) -> Result<SigmetCollection> {
+        self.fetch(&request::aviation_sigmets_atsu_date(atsu, date)).await
+    }
+    
+    pub async fn aviation_sigmets_atsu_date_time(&self, atsu: &str, date: NaiveDate, time: NaiveTime) -> Result<Sigmet> {
+        self.fetch(&request::aviation_sigmets_atsu_date_time(atsu, date, time)).await
+    }
+    
+    /// Glossary terms mapped to their definitions.
+    pub async fn glossary(&self) -> Result<HashMap<String, String>> {
+        let glossary: Glossary = self.fetch(&request::glossary()).await?;
+        
+        Ok(glossary.into_map())
+    }
+    
+    pub async fn gridpoints(&self, id: &str, x: usize, y: usize) -> Result<Gridpoint> {
+        self.fetch(&request::gridpoints(id, x, y)).await
+    }
+    
+    pub async fn gridpoints_forecast(&self, id: &str, x: usize, y: usize, units: Option<ForecastUnits>, feature_flags: Option<&[&str]>) -> Result<Forecast> {
+        self.fetch(&request::gridpoints_forecast(id, x, y, units, feature_flags)).await
+    }
+    
+    pub async fn gridpoints_forecast_hourly(&self, id: &str, x: usize, y: usize, units: Option<ForecastUnits>, feature_flags: Option<&[&str]>) -> Result<Forecast> {
+        self.fetch(&request::gridpoints_forecast_hourly(id, x, y, units, feature_flags)).await
+    }
+    
+    pub async fn gridpoints_stations(&self, id: &str, x: usize, y: usize) -> Result<GridpointStations> {
+        self.fetch(&request::gridpoints_stations(id, x, y)).await
+    }
+    
+    pub async fn radar_stations(&self, station_types: Option<&[&str]>, reporting_host: Option<&str>, host: Option<&str>) -> Result<RadarStationCollection> {
+        self.fetch(&request::radar_stations(station_types, reporting_host, host)).await
+    }
+    
+    pub async fn radar_stations_id(&self, id: &str, reporting_host: Option<&str>, host: Option<&str>) -> Result<RadarStation> {
+        self.fetch(&request::radar_stations_id(id, reporting_host, host)).await
+    }
+    
+    pub async fn radar_stations_alarms(&self, id: &str) -> Result<RadarStationAlarmCollection> {
+        self.fetch(&request::radar_stations_alarms(id)).await
+    }
+    
+    pub async fn radar_servers(&self, reporting_host: Option<&str>) -> Result<RadarServerCollection> {
+        self.fetch(&request::radar_servers(reporting_host)).await
+    }
+    
+    pub async fn radar_servers_id(&self, id: &str, reporting_host: Option<&str>) -> Result<RadarServer> {
+        self.fetch(&request::radar_servers_id(id, reporting_host)).await
+    }
+    
+    pub async fn radar_queues(&self, host: &str, filter: &RadarQueueFilter) -> Result<RadarQueueCollection> {
+        self.fetch(&request::radar_queues(host, filter)).await
+    }
+    
+    /// `time` and `interval` are ISO 8601 interval and duration strings respectively.
+    pub async fn radar_profilers(&self, id: &str, time: Option<&str>, interval: Option<&str>) -> Result<RadarProfiler> {
+        self.fetch(&request::radar_profilers(id, time, interval)).await
+    }
+    
+    pub async fn stations_observations(&self, id: &str, start: Option<DateTime<Utc>>, end: Option<DateTime<Utc>>, limit: Option<usize>) -> Result<ObservationCollection> {
+        self.fetch(&request::stations_observations(id, start, end, limit)).await
+    }
+    
+    pub async fn stations_observations_latest(&self, id: &str, require_qc: Option<bool>) -> Result<Observation> {
+        self.fetch(&request::stations_observations_latest(id, require_qc)).await
+    }
+    
+    pub async fn stations_tafs(&self, id: &str) -> Result<TafCollection> {
+        self.fetch(&request::stations_tafs(id)).await
+    }
+    
+    /// Returns the TAF issued at the given date and time as served by the API, which is an IWXXM (XML) document.
+    /// 
+    /// The traditional TAF text, as decoded by [`Taf::decode`](crate::endpoints::stations::taf::Taf::decode), is available
+    /// from the `TAF` text products, e.g. `latest_product("TAF", "IAD")`.
+    pub async fn stations_tafs_time(&self, id: &str, date: NaiveDate, time: NaiveTime) -> Result<String> {
+        self.execute(&request::stations_tafs_time(id, date, time), ApiResponse::text).await
+    }
+    
+    pub async fn stations_observations_time(&self, id: &str, time: DateTime<Utc>) -> Result<Observation> {
+        self.fetch(&request::stations_observations_time(id, time)).await
+    }
+    
+    pub async fn stations(&self, ids: Option<&[&str]>, states: Option<&[&str]>, limit: Option<usize>) -> Result<ObservationStationCollection> {
+        self.fetch(&request::stations(ids, states, limit)).await
+    }
+    
+    pub async fn stations_id(&self, id: &str) -> Result<ObservationStation> {
+        self.fetch(&request::stations_id(id)).await
+    }
+    
+    pub async fn points(&self, lat: f64, lon: f64) -> Result<Point> {
+        self.fetch(&request::points(lat, lon)).await
+    }
+    
+    pub async fn icons(&self) -> Result<IconCollection> {
+        self.fetch(&request::icons()).await
+    }
+    
+    /// Downloads the image for a single or dual-condition icon (`/icons/{set}/{timeOfDay}/{first}[/{second}]`).
+    pub async fn icons_image(&self, set: IconSet, time_of_day: TimeOfDay, first: &IconCondition, second: Option<&IconCondition>, size: Option<IconSize>, font_size: Option<usize>) -> Result<Vec<u8>> {
+        self.execute(&request::icons_image(set, time_of_day, first, second, size, font_size), ApiResponse::bytes).await
+    }
+    
+    /// Downloads the image behind an icon URL, such as [`Observation::icon`].
+    pub async fn icons_url(&self, url: &Url) -> Result<Vec<u8>> {
+        self.execute(&request::icons_url(url), ApiResponse::bytes).await
+    }
+    
+    pub async fn offices(&self, id: &str) -> Result<Office> {
+        self.fetch(&request::offices(id)).await
+    }
+    
+    /// Follows an office URL, such as [`Point::forecast_office`] or [`Gridpoint::forecast_office`].
+    pub async fn offices_url(&self, url: &Url) -> Result<Office> {
+        self.fetch(&ApiRequest::url(url)).await
+    }
+    
+    pub async fn offices_headlines(&self, id: &str) -> Result<OfficeHeadlineCollection> {
+        self.fetch(&request::offices_headlines(id)).await
+    }
+    
+    pub async fn offices_headlines_id(&self, id: &str, headline_id: &str) -> Result<OfficeHeadline> {
+        self.fetch(&request::offices_headlines_id(id, headline_id)).await
+    }
+    
+    pub async fn products(&self, filter: &ProductFilter) -> Result<ProductCollection> {
+        self.fetch(&request::products(filter)).await
+    }
+    
+    pub async fn products_id(&self, id: &str) -> Result<Product> {
+        self.fetch(&request::products_id(id)).await
+    }
+    
+    pub async fn products_types(&self) -> Result<ProductTypeCollection> {
+        self.fetch(&request::products_types()).await
+    }
+    
+    pub async fn products_types_id(&self, type_id: &str) -> Result<ProductCollection> {
+        self.fetch(&request::products_types_id(type_id)).await
+    }
+    
+    pub async fn products_types_locations(&self, type_id: &str, location_id: &str) -> Result<ProductCollection> {
+        self.fetch(&request::products_types_locations(type_id, location_id)).await
+    }
+    
+    pub async fn products_locations(&self) -> Result<ProductLocations> {
+        self.fetch(&request::products_locations()).await
+    }
+    
+    /// Most recent product of a type issued for a location, e.g. `latest_product("AFD", "LWX")` for the latest Area
+    /// Forecast Discussion from Sterling, VA.
+    pub async fn latest_product(&self, type_id: &str, location_id: &str) -> Result<Product> {
+        self.fetch(&request::latest_product(type_id, location_id)).await
+    }
+    
+    pub async fn zones(&self, filter: &ZoneFilter) -> Result<ZoneCollection> {
+        self.fetch(&request::zones(filter)).await
+    }
+    
+    pub async fn zones_id(&self, zone_type: ZoneType, id: &str, effective: Option<DateTime<Utc>>) -> Result<Zone> {
+        self.fetch(&request::zones_id(zone_type, id, effective)).await
+    }
+    
+    /// Follows a zone URL, such as [`Point::forecast_zone`] or [`ObservationStation::county`].
+    pub async fn zones_url(&self, url: &Url) -> Result<Zone> {
+        self.fetch(&ApiRequest::url(url)).await
+    }
+    
+    pub async fn zones_forecast(&self, zone_type: ZoneType, id: &str) -> Result<ZoneForecast> {
+        self.fetch(&request::zones_forecast(zone_type, id)).await
+    }
+    
+    pub async fn zones_observations(&self, id: &str, start: Option<DateTime<Utc>>, end: Option<DateTime<Utc>>, limit: Option<usize>) -> Result<ObservationCollection> {
+        self.fetch(&request::zones_observations(id, start, end, limit)).await
+    }
+    
+    pub async fn zones_stations(&self, id: &str, limit: Option<usize>) -> Result<ObservationStationCollection> {
+        self.fetch(&request::zones_stations(id, limit)).await
+    }
+    
+    
+    /// Textual forecast for the grid cell containing the given coordinates.
+    /// 
+    /// Uses the `forecast` URL from the (cached) `/points` lookup.
+    pub async fn forecast_at(&self, lat: f64, lon: f64) -> Result<Forecast> {
+        let point = self.cached_point(lat, lon).await?;
+        
+        self.fetch(&ApiRequest::url(&point.forecast)).await
+    }
+    
+    /// Hourly forecast for the grid cell containing the given coordinates.
+    /// 
+    /// Uses the `forecastHourly` URL from the (cached) `/points` lookup.
+    pub async fn hourly_forecast_at(&self, lat: f64, lon: f64) -> Result<Forecast> {
+        let point = self.cached_point(lat, lon).await?;
+        
+        self.fetch(&ApiRequest::url(&point.forecast_hourly)).await
+    }
+    
+    /// Raw gridded forecast data for the grid cell containing the given coordinates.
+    /// 
+    /// Uses the `forecastGridData` URL from the (cached) `/points` lookup.
+    pub async fn grid_data_at(&self, lat: f64, lon: f64) -> Result<Gridpoint> {
+        let point = self.cached_point(lat, lon).await?;
+        
+        self.fetch(&ApiRequest::url(&point.forecast_grid_data)).await
+    }
+    
+    /// Latest observation from the closest station to the given coordinates.
+    /// 
+    /// Stations are taken from the `observationStations` URL of the (cached) `/points` lookup, which lists them by
+    /// distance. Returns `None` if no station is associated with the point.
+    pub async fn nearest_observation_at(&self, lat: f64, lon: f64) -> Result<Option<Observation>> {
+        let point = self.cached_point(lat, lon).await?;
+        let stations: ObservationStationCollection = self.fetch(&ApiRequest::url(&point.observation_stations)).await?;
+        
+        match stations.graph.first() {
+            Some(station) => self.fetch(&ApiRequest::url(format!("{}/observations/latest", station.id))).await.map(Some),
+            None => Ok(None)
+        }
+    }
+    
+    /// Forgets all `/points` lookups made by the `*_at` helpers.
+    pub fn clear_point_cache(&self) {
+        self.point_cache.clear();
+    }
+    
+    async fn cached_point(&self, lat: f64, lon: f64) -> Result<Point> {
+        if let Some(point) = self.point_cache.get(lat, lon) {
+            return Ok(point);
+        }
+        
+        let point = self.points(lat, lon).await?;
+        self.point_cache.insert(lat, lon, point.clone());
+        
+        Ok(point)
+    }
+    
+    
+    pub async fn radar(&self, id: &str, radar_type: RadarType) -> Result<Vec<RemoteFile>> {
+        let request = request::radar(id, radar_type);
+        let url = self.pipeline.rebased(&request).url;
+        
+        self.execute(&request, |resp| parse_listing(&url, &resp.text()?)).await
+    }
+    
+    
+    pub(crate) async fn fetch<T: DeserializeOwned>(&self, request: &ApiRequest) -> Result<T> {
+        self.execute(request, ApiResponse::json).await
+    }
+    
+    async fn execute<T>(&self, request: &ApiRequest, read: impl Fn(ApiResponse) -> Result<T>) -> Result<T> {
+        Ok(self.execute_cached(request, read).await?.value)
+    }
+    
+    async fn execute_cached<T>(&self, request: &ApiRequest, read: impl Fn(ApiResponse) -> Result<T>) -> Result<Cached<T>> {
+        self.pipeline.execute(
+            request,
+            read,
+            |request| async move { self.transport.send(&request).await },
+            tokio::time::sleep,
+        ).await
+    }
+}
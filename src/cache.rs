@@ -0,0 +1,497 @@
+//! HTTP response caching.
+//!
+//! Responses are kept for as long as their `Cache-Control: max-age` or `Expires` headers allow. Once stale, they are
+//! revalidated with `If-None-Match` / `If-Modified-Since`, so an unchanged resource costs a `304` instead of a full
+//! download. Enable with [`NwsClient::with_cache`](crate::NwsClient::with_cache).
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime};
+use chrono::DateTime;
+use serde::{Serialize, Deserialize};
+use crate::request::{ApiRequest, ApiResponse};
+
+
+/// A stored response and the time until which it may be used without asking the server.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CachedResponse {
+    pub response: ApiResponse,
+    pub expires: SystemTime,
+}
+impl CachedResponse {
+    pub fn is_fresh(&self) -> bool {
+        SystemTime::now() < self.expires
+    }
+}
+
+/// Storage backend for cached responses.
+pub trait CacheStore: Debug + Send + Sync {
+    fn get(&self, key: &str) -> Option<CachedResponse>;
+    fn put(&self, key: &str, entry: CachedResponse);
+    fn remove(&self, key: &str);
+}
+
+/// In-memory store keeping the `capacity` most recently used responses.
+#[derive(Debug)]
+pub struct MemoryCache {
+    entries: Lru<CachedResponse>,
+}
+impl MemoryCache {
+    pub fn new(capacity: usize) -> Self { Self {
+        entries: Lru::new(capacity),
+    }}
+}
+impl CacheStore for MemoryCache {
+    fn get(&self, key: &str) -> Option<CachedResponse> {
+        self.entries.get(key)
+    }
+    
+    fn put(&self, key: &str, entry: CachedResponse) {
+        self.entries.insert(key, entry);
+    }
+    
+    fn remove(&self, key: &str) {
+        self.entries.remove(key);
+    }
+}
+
+/// A map keeping only the `capacity` most recently used values, shared by [`MemoryCache`] and the `/points` cache.
+#[derive(Debug)]
+pub(crate) struct Lru<V> {
+    capacity: usize,
+    entries: Mutex<LruEntries<V>>,
+}
+impl<V: Clone> Lru<V> {
+    pub fn new(capacity: usize) -> Self { Self {
+        capacity: capacity.max(1),
+        entries: Mutex::new(LruEntries { entries: HashMap::new(), clock: 0 }),
+    }}
+    
+    pub fn get(&self, key: &str) -> Option<V> {
+        let mut lru = self.entries.lock().ok()?;
+        lru.clock += 1;
+        let clock = lru.clock;
+        let (value, used) = lru.entries.get_mut(key)?;
+        *used = clock;
+        
+        Some(value.clone())
+    }
+    
+    pub fn insert(&self, key: &str, value: V) {
+        let Ok(mut lru) = self.entries.lock() else { return; };
+        lru.clock += 1;
+        let clock = lru.clock;
+        lru.entries.insert(key.to_owned(), (value, clock));
+        
+        while lru.entries.len() > self.capacity {
+            let Some(oldest) = lru.entries.iter().min_by_key(|(_, (_, used))| *used).map(|(key, _)| key.clone()) else { break; };
+            lru.entries.remove(&oldest);
+        }
+    }
+    
+    pub fn remove(&self, key: &str) {
+        if let Ok(mut lru) = self.entries.lock() {
+            lru.entries.remove(key);
+        }
+    }
+    
+    pub fn clear(&self) {
+        if let Ok(mut lru) = self.entries.lock() {
+            lru.entries.clear();
+        }
+    }
+}
+
+/// Entries with the value of `clock` when they were last used.
+#[derive(Debug)]
+struct LruEntries<V> {
+    entries: HashMap<String, (V, u64)>,
+    clock: u64,
+}
+
+/// On-disk store, so cached data survives restarts. Each response is kept in one file: its metadata as a line of
+/// JSON, followed by the raw body.
+#[derive(Debug)]
+pub struct DiskCache {
+    dir: PathBuf,
+}
+impl DiskCache {
+    /// Stores responses under `dir`, which is created if needed.
+    pub fn new(dir: impl Into<PathBuf>) -> io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        
+        Ok(Self { dir })
+    }
+    
+    fn path(&self, key: &str) -> PathBuf {
+        // FNV-1a, which unlike the std hasher is stable across Rust versions.
+        let hash = key.bytes().fold(0xcbf29ce484222325u64, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3));
+        
+        self.dir.join(format!("{:016x}.entry", hash))
+    }
+}
+
+/// Distinguishes the temporary files of concurrent writers within a process.
+static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Serialize, Deserialize)]
+struct DiskEntry {
+    key: String,
+    status: u16,
+    headers: Vec<(String, String)>,
+    expires: SystemTime,
+}
+
+impl CacheStore for DiskCache {
+    fn get(&self, key: &str) -> Option<CachedResponse> {
+        let mut body = fs::read(self.path(key)).ok()?;
+        let split = body.iter().position(|&byte| byte == b'\n')?;
+        let meta: DiskEntry = serde_json::from_slice(&body[..split]).ok()?;
+        if meta.key != key { return None; }
+        body.drain(..=split);
+        
+        Some(CachedResponse {
+            response: ApiResponse { status: meta.status, headers: meta.headers, body },
+            expires: meta.expires,
+        })
+    }
+    
+    fn put(&self, key: &str, entry: CachedResponse) {
+        let path = self.path(key);
+        let meta = DiskEntry {
+            key: key.to_owned(),
+            status: entry.response.status,
+            headers: entry.response.headers,
+            expires: entry.expires,
+        };
+        let Ok(mut contents) = serde_json::to_vec(&meta) else { return; };
+        contents.push(b'\n');
+        contents.extend(entry.response.body);
+        
+        // Each writer fills its own temporary file and swaps it in whole, so readers see either the old entry or the
+        // new one, never a mix of two writes.
+        let tmp = path.with_extension(format!("{}.{}.tmp", std::process::id(), TMP_COUNTER.fetch_add(1, Ordering::Relaxed)));
+        let written = fs::write(&tmp, contents).and_then(|_| fs::rename(&tmp, &path));
+        if written.is_err() {
+            let _ = fs::remove_file(tmp);
+            self.remove(key);
+        }
+    }
+    
+    fn remove(&self, key: &str) {
+        let _ = fs::remove_file(self.path(key));
+    }
+}
+
+
+/// Where the data of a response came from.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CacheStatus {
+    /// Served from the cache without contacting the server.
+    Hit,
+    /// The server confirmed with a `304` that the cached copy is still current.
+    Revalidated,
+    /// Downloaded and stored in the cache.
+    Miss,
+    /// Downloaded and not stored, because caching is disabled or the response may not be cached.
+    Bypass,
+}
+
+/// Counts of cache outcomes since the client was created, shared by all its clones.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub revalidated: u64,
+    pub misses: u64,
+    pub bypassed: u64,
+}
+
+/// A value together with where its response came from.
+#[derive(Clone, Debug)]
+pub struct Cached<T> {
+    pub value: T,
+    pub cache_status: CacheStatus,
+}
+
+
+/// The cache outcome of the last request made through one client handle. Cloning a client starts its clone with a
+/// copy rather than sharing it, so each handle reports its own requests.
+#[derive(Debug, Default)]
+pub(crate) struct LastCacheStatus(Mutex<Option<CacheStatus>>);
+impl LastCacheStatus {
+    pub fn get(&self) -> Option<CacheStatus> {
+        self.0.lock().ok().and_then(|status| *status)
+    }
+    
+    pub fn set(&self, status: Option<CacheStatus>) {
+        if let Ok(mut last) = self.0.lock() {
+            *last = status;
+        }
+    }
+}
+impl Clone for LastCacheStatus {
+    fn clone(&self) -> Self {
+        Self(Mutex::new(self.get()))
+    }
+}
+
+
+/// The outcome of looking a request up before sending it.
+pub(crate) enum Lookup {
+    Fresh(ApiResponse),
+    /// Send `request`, which carries validators for `stale` if there is a stale copy.
+    Send {
+        request: ApiRequest,
+        stale: Option<CachedResponse>,
+    },
+}
+
+/// Caching logic shared by the blocking and async clients, on top of a [`CacheStore`].
+#[derive(Debug)]
+pub(crate) struct ResponseCache {
+    store: Box<dyn CacheStore>,
+    hits: AtomicU64,
+    revalidated: AtomicU64,
+    misses: AtomicU64,
+    bypassed: AtomicU64,
+}
+impl ResponseCache {
+    pub fn new(store: impl CacheStore + 'static) -> Self { Self {
+        store: Box::new(store),
+        hits: AtomicU64::new(0),
+        revalidated: AtomicU64::new(0),
+        misses: AtomicU64::new(0),
+        bypassed: AtomicU64::new(0),
+    }}
+    
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            revalidated: self.revalidated.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            bypassed: self.bypassed.load(Ordering::Relaxed),
+        }
+    }
+    
+    /// The URL plus the headers that can change the response, such as `Accept` and `Feature-Flags`.
+    fn key(request: &ApiRequest) -> String {
+        let mut key = request.full_url().map(String::from).unwrap_or_else(|| request.url.clone());
+        for (name, value) in &request.headers {
+            if !name.to_ascii_lowercase().starts_with("if-") {
+                key = format!("{} {}={}", key, name, value);
+            }
+        }
+        
+        key
+    }
+    
+    pub fn lookup(&self, request: &ApiRequest) -> Lookup {
+        let stale = match self.store.get(&Self::key(request)) {
+            Some(entry) if entry.is_fresh() => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                return Lookup::Fresh(entry.response);
+            }
+            entry => entry,
+        };
+        
+        let mut request = request.clone();
+        if let Some(stale) = &stale {
+            if let Some(etag) = stale.response.header("ETag") {
+                request = request.header("If-None-Match", etag);
+            }
+            if let Some(last_modified) = stale.response.header("Last-Modified") {
+                request = request.header("If-Modified-Since", last_modified);
+            }
+        }
+        
+        Lookup::Send { request, stale }
+    }
+    
+    /// Stores the response to `request` if allowed and returns the response to use: the cached copy for a `304`.
+    pub fn store(&self, request: &ApiRequest, stale: Option<CachedResponse>, response: ApiResponse) -> (ApiResponse, CacheStatus) {
+        let key = Self::key(request);
+        
+        let (response, status) = match stale {
+            Some(stale) if response.status == 304 => {
+                let mut cached = stale.response;
+                for (name, value) in response.headers {
+                    cached.headers.retain(|(existing, _)| !existing.eq_ignore_ascii_case(&name));
+                    cached.headers.push((name, value));
+                }
+                (cached, CacheStatus::Revalidated)
+            }
+            // Errors are passed on untouched, keeping any stale copy for the next attempt.
+            _ if response.status != 200 => {
+                self.bypassed.fetch_add(1, Ordering::Relaxed);
+                return (response, CacheStatus::Bypass);
+            }
+            _ => (response, CacheStatus::Miss),
+        };
+        
+        match expiry(&response) {
+            Some(expires) => self.store.put(&key, CachedResponse { response: response.clone(), expires }),
+            None => {
+                self.store.remove(&key);
+                self.bypassed.fetch_add(1, Ordering::Relaxed);
+                return (response, CacheStatus::Bypass);
+            }
+        }
+        match status {
+            CacheStatus::Revalidated => self.revalidated.fetch_add(1, Ordering::Relaxed),
+            _ => self.misses.fetch_add(1, Ordering::Relaxed),
+        };
+        
+        (response, status)
+    }
+}
+
+/// Until when a response may be used without revalidating, or `None` if it may not be stored at all.
+/// 
+/// Responses without freshness headers are stored as already stale if they have a validator, since revalidating
+/// them is still cheaper than a full download.
+fn expiry(response: &ApiResponse) -> Option<SystemTime> {
+    let now = SystemTime::now();
+    let cache_control: Vec<String> = response.header("Cache-Control").unwrap_or_default()
+        .split(',')
+        .map(|directive| directive.trim().to_ascii_lowercase())
+        .collect();
+    if cache_control.iter().any(|directive| directive == "no-store") {
+        return None;
+    }
+    let has_validator = response.header("ETag").is_some() || response.header("Last-Modified").is_some();
+    if cache_control.iter().any(|directive| directive == "no-cache") {
+        return has_validator.then_some(now);
+    }
+    
+    let max_age = cache_control.iter().find_map(|directive| directive.strip_prefix("max-age=")?.trim_matches('"').parse::<u64>().ok());
+    let lifetime = match max_age {
+        Some(max_age) => {
+            let age = response.header("Age").and_then(|age| age.trim().parse().ok()).unwrap_or(0);
+            Some(Duration::from_secs(max_age.saturating_sub(age)))
+        }
+        None => response.header("Expires").map(|expires| {
+            DateTime::parse_from_rfc2822(expires.trim()).ok()
+                .and_then(|expires| SystemTime::from(expires).duration_since(now).ok())
+                .unwrap_or_default()
+        }),
+    };
+    
+    match lifetime {
+        Some(lifetime) if !lifetime.is_zero() => Some(now + lifetime),
+        _ => has_validator.then_some(now),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn response(headers: &[(&str, &str)]) -> ApiResponse {
+        headers.iter().fold(ApiResponse::new(200, "{}"), |response, (name, value)| response.with_header(*name, *value))
+    }
+    
+    fn lifetime(headers: &[(&str, &str)]) -> Option<Duration> {
+        let before = SystemTime::now();
+        let expires = expiry(&response(headers))?;
+        
+        Some(expires.duration_since(before).unwrap_or_default())
+    }
+    
+    #[test]
+    fn expiry_follows_max_age_minus_age() {
+        let lifetime = lifetime(&[("Cache-Control", "public, max-age=300"), ("Age", "100")]).unwrap();
+        assert!(lifetime > Duration::from_secs(195) && lifetime <= Duration::from_secs(201), "{:?}", lifetime);
+    }
+    
+    #[test]
+    fn expiry_follows_expires_header() {
+        let expires = (chrono::Utc::now() + chrono::Duration::seconds(600)).to_rfc2822();
+        let lifetime = lifetime(&[("Expires", &expires)]).unwrap();
+        assert!(lifetime > Duration::from_secs(590) && lifetime <= Duration::from_secs(601), "{:?}", lifetime);
+    }
+    
+    #[test]
+    fn expiry_without_freshness() {
+        assert_eq!(lifetime(&[("Cache-Control", "no-store"), ("ETag", "\"a\"")]), None);
+        assert_eq!(lifetime(&[]), None);
+        assert_eq!(lifetime(&[("Cache-Control", "max-age=0")]), None);
+        assert_eq!(lifetime(&[("Expires", "not a date")]), None);
+        
+        // Responses that can be revalidated are kept, but already stale.
+        let stale = |lifetime: Option<Duration>| lifetime.is_some_and(|lifetime| lifetime < Duration::from_secs(1));
+        assert!(stale(lifetime(&[("Cache-Control", "no-cache"), ("ETag", "\"a\"")])));
+        assert!(stale(lifetime(&[("Last-Modified", "Sat, 01 Jan 2022 12:00:00 GMT")])));
+        assert_eq!(lifetime(&[("Cache-Control", "no-cache")]), None);
+    }
+    
+    #[test]
+    fn not_modified_merges_headers_into_cached_copy() {
+        let cache = ResponseCache::new(MemoryCache::new(4));
+        let request = ApiRequest::url("https://api.weather.gov/glossary");
+        let stale = CachedResponse {
+            response: ApiResponse::new(200, "cached body")
+                .with_header("ETag", "\"v1\"")
+                .with_header("Content-Type", "application/ld+json")
+                .with_header("Cache-Control", "max-age=0"),
+            expires: SystemTime::now(),
+        };
+        let not_modified = ApiResponse::new(304, Vec::new())
+            .with_header("cache-control", "max-age=60")
+            .with_header("X-Request-Id", "abc");
+        
+        let (response, status) = cache.store(&request, Some(stale), not_modified);
+        assert_eq!(status, CacheStatus::Revalidated);
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body, b"cached body");
+        assert_eq!(response.header("ETag"), Some("\"v1\""));
+        assert_eq!(response.header("Content-Type"), Some("application/ld+json"));
+        assert_eq!(response.header("Cache-Control"), Some("max-age=60"));
+        assert_eq!(response.header("X-Request-Id"), Some("abc"));
+        assert_eq!(response.headers.iter().filter(|(name, _)| name.eq_ignore_ascii_case("Cache-Control")).count(), 1);
+        
+        // The merged copy is stored, fresh again.
+        assert!(matches!(cache.lookup(&request), Lookup::Fresh(cached) if cached == response));
+        assert_eq!(cache.stats().revalidated, 1);
+    }
+    
+    #[test]
+    fn lru_evicts_least_recently_used() {
+        let lru = Lru::new(2);
+        lru.insert("a", 1);
+        lru.insert("b", 2);
+        assert_eq!(lru.get("a"), Some(1));
+        lru.insert("c", 3);
+        
+        assert_eq!(lru.get("b"), None);
+        assert_eq!(lru.get("a"), Some(1));
+        assert_eq!(lru.get("c"), Some(3));
+        lru.clear();
+        assert_eq!(lru.get("a"), None);
+    }
+    
+    #[test]
+    fn disk_cache_round_trip() {
+        let dir = std::env::temp_dir().join(format!("nws-api-rs-cache-{}", std::process::id()));
+        let cache = DiskCache::new(&dir).unwrap();
+        let entry = CachedResponse {
+            response: ApiResponse::new(200, b"line one\nline two".to_vec()).with_header("ETag", "\"a\""),
+            expires: SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+        };
+        
+        cache.put("key", entry.clone());
+        assert_eq!(cache.get("key"), Some(entry));
+        assert_eq!(cache.get("other"), None);
+        cache.remove("key");
+        assert_eq!(cache.get("key"), None);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+        
+        let _ = fs::remove_dir_all(dir);
+    }
+}
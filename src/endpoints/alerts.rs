@@ -0,0 +1,379 @@
+use std::cmp::{max, min};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use chrono::{DateTime, FixedOffset};
+use serde::{Serialize, Deserialize};
+use url::Url;
+use wkt::Wkt;
+use crate::NwsClient;
+#[cfg(feature = "async")]
+use crate::NwsAsyncClient;
+use crate::request::ApiRequest;
+use crate::vtec::Vtec;
+use super::{serialize_wkt_opt, deserialize_wkt_opt};
+
+
+/// The code denoting the appropriate handling of an alert message.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Status {
+    Actual,
+    Exercise,
+    System,
+    Test,
+    Draft,
+}
+impl Display for Status {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        use Status::*;
+        write!(f, "{}", match self {
+            Actual => "actual",
+            Exercise => "exercise",
+            System => "system",
+            Test => "test",
+            Draft => "draft",
+        })
+    }
+}
+
+/// The code denoting the nature of an alert message.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MessageType {
+    Alert,
+    Update,
+    Cancel,
+    Ack,
+    Error,
+}
+impl Display for MessageType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        use MessageType::*;
+        write!(f, "{}", match self {
+            Alert => "alert",
+            Update => "update",
+            Cancel => "cancel",
+            Ack => "ack",
+            Error => "error",
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Category {
+    Met,
+    Geo,
+    Safety,
+    Security,
+    Rescue,
+    Fire,
+    Health,
+    Env,
+    Transport,
+    Infra,
+    #[serde(rename = "CBRNE")]
+    Cbrne,
+    Other,
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Severity {
+    Extreme,
+    Severe,
+    Moderate,
+    Minor,
+    Unknown,
+}
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Certainty {
+    Observed,
+    Likely,
+    Possible,
+    Unlikely,
+    Unknown,
+}
+impl Display for Certainty {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Urgency {
+    Immediate,
+    Expected,
+    Future,
+    Past,
+    Unknown,
+}
+impl Display for Urgency {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Response {
+    Shelter,
+    Evacuate,
+    Prepare,
+    Execute,
+    Avoid,
+    Monitor,
+    Assess,
+    AllClear,
+    None,
+}
+
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct AlertGeocode {
+    #[serde(rename = "SAME", default)]
+    pub same: Vec<String>,
+    #[serde(rename = "UGC", default)]
+    pub ugc: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AlertReference {
+    #[serde(rename = "@id")]
+    pub id: Url,
+    pub identifier: String,
+    pub sender: String,
+    pub sent: DateTime<FixedOffset>,
+}
+
+/// Well-known entries of the alert `parameters` object. Any other parameters are kept in `other`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct AlertParameters {
+    #[serde(rename = "AWIPSidentifier", default)]
+    pub awips_identifier: Vec<String>,
+    #[serde(rename = "WMOidentifier", default)]
+    pub wmo_identifier: Vec<String>,
+    #[serde(rename = "NWSheadline", default)]
+    pub nws_headline: Vec<String>,
+    #[serde(rename = "VTEC", default)]
+    pub vtec: Vec<String>,
+    #[serde(rename = "eventEndingTime", default)]
+    pub event_ending_time: Vec<DateTime<FixedOffset>>,
+    #[serde(rename = "expiredReferences", default)]
+    pub expired_references: Vec<String>,
+    #[serde(flatten)]
+    pub other: HashMap<String, Vec<String>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Alert {
+    #[serde(default, deserialize_with = "deserialize_wkt_opt", serialize_with = "serialize_wkt_opt")]
+    pub geometry: Option<Wkt<f64>>,
+    #[serde(rename = "@id")]
+    pub id: Url,
+    #[serde(rename = "@type")]
+    pub kind: String,
+    #[serde(rename = "id")]
+    pub identifier: String,
+    pub area_desc: String,
+    #[serde(default)]
+    pub geocode: AlertGeocode,
+    #[serde(default)]
+    pub affected_zones: Vec<Url>,
+    #[serde(default)]
+    pub references: Vec<AlertReference>,
+    pub sent: DateTime<FixedOffset>,
+    pub effective: DateTime<FixedOffset>,
+    pub onset: Option<DateTime<FixedOffset>>,
+    pub expires: DateTime<FixedOffset>,
+    pub ends: Option<DateTime<FixedOffset>>,
+    pub status: Status,
+    pub message_type: MessageType,
+    pub category: Category,
+    pub severity: Severity,
+    pub certainty: Certainty,
+    pub urgency: Urgency,
+    pub event: String,
+    pub sender: String,
+    pub sender_name: String,
+    pub headline: Option<String>,
+    pub description: String,
+    pub instruction: Option<String>,
+    pub response: Response,
+    #[serde(default)]
+    pub parameters: AlertParameters,
+}
+impl Alert {
+    /// Parses the `VTEC` parameter strings, skipping any that are malformed.
+    pub fn parse_vtec(&self) -> Vec<Vtec> {
+        self.parameters.vtec.iter().filter_map(Vtec::parse).collect()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Pagination {
+    pub next: Url,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertCollection {
+    #[serde(rename = "@graph")]
+    pub graph: Vec<Alert>,
+    pub title: Option<String>,
+    pub updated: Option<DateTime<FixedOffset>>,
+    pub pagination: Option<Pagination>,
+}
+impl AlertCollection {
+    /// Request for the page after this one, which was fetched with `current`.
+    /// 
+    /// `None` once a page comes back empty or without a next link, or if the next link points back at `current`. Only
+    /// the path and query are compared, since `current` may have been sent to a different base URL.
+    pub(crate) fn next_page(&self, current: &ApiRequest) -> Option<ApiRequest> {
+        if self.graph.is_empty() { return None; }
+        let next = &self.pagination.as_ref()?.next;
+        let same_page = current.full_url().is_some_and(|current| current.path() == next.path() && current.query() == next.query());
+        
+        (!same_page).then(|| ApiRequest::url(next))
+    }
+}
+
+/// Number of active alerts, broken down by land/marine and by region, area and zone.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AlertCount {
+    pub total: usize,
+    pub land: usize,
+    pub marine: usize,
+    pub regions: HashMap<String, usize>,
+    pub areas: HashMap<String, usize>,
+    pub zones: HashMap<String, usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertTypes {
+    pub event_types: Vec<String>,
+}
+
+
+/// Query parameters accepted by the alert listing endpoints.
+/// 
+/// Empty lists and `None` values are omitted from the request.
+#[derive(Clone, Debug, Default)]
+pub struct AlertFilter {
+    pub status: Vec<Status>,
+    pub message_type: Vec<MessageType>,
+    pub event: Vec<String>,
+    pub code: Vec<String>,
+    /// State/territory codes, or marine area codes.
+    pub area: Vec<String>,
+    /// Latitude and longitude.
+    pub point: Option<(f64, f64)>,
+    /// Marine region codes.
+    pub region: Vec<String>,
+    pub zone: Vec<String>,
+    pub urgency: Vec<Urgency>,
+    pub severity: Vec<Severity>,
+    pub certainty: Vec<Certainty>,
+    pub limit: Option<usize>,
+}
+impl AlertFilter {
+    pub(crate) fn params(&self) -> Vec<(&'static str, String)> {
+        fn join<T: ToString>(values: &[T]) -> String {
+            values.iter().map(|v| v.to_string()).collect::<Vec<String>>().join(",")
+        }
+        
+        let mut params = Vec::new();
+        if !self.status.is_empty() { params.push(("status", join(&self.status))); }
+        if !self.message_type.is_empty() { params.push(("message_type", join(&self.message_type))); }
+        if !self.event.is_empty() { params.push(("event", join(&self.event))); }
+        if !self.code.is_empty() { params.push(("code", join(&self.code))); }
+        if !self.area.is_empty() { params.push(("area", join(&self.area))); }
+        if let Some((lat, lon)) = self.point { params.push(("point", format!("{:.4},{:.4}", lat, lon))); }
+        if !self.region.is_empty() { params.push(("region", join(&self.region))); }
+        if !self.zone.is_empty() { params.push(("zone", join(&self.zone))); }
+        if !self.urgency.is_empty() { params.push(("urgency", join(&self.urgency))); }
+        if !self.severity.is_empty() { params.push(("severity", join(&self.severity))); }
+        if !self.certainty.is_empty() { params.push(("certainty", join(&self.certainty))); }
+        if let Some(limit) = self.limit { params.push(("limit", max(1, min(500, limit)).to_string())); }
+        
+        params
+    }
+}
+
+
+/// Iterator over every alert returned by the `/alerts` endpoint, following `pagination.next` links until a page
+/// comes back empty or without a next link.
+/// 
+/// Created by [`NwsClient::alerts_iter`]. Each page is only requested once the alerts of the previous page are consumed.
+#[derive(Debug)]
+pub struct AlertIter<'a> {
+    client: &'a NwsClient,
+    next: Option<ApiRequest>,
+    page: std::vec::IntoIter<Alert>,
+}
+impl<'a> AlertIter<'a> {
+    pub(crate) fn new(client: &'a NwsClient, first: ApiRequest) -> Self { Self {
+        client,
+        next: Some(first),
+        page: Vec::new().into_iter(),
+    }}
+}
+impl Iterator for AlertIter<'_> {
+    type Item = crate::Result<Alert>;
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(alert) = self.page.next() {
+                return Some(Ok(alert));
+            }
+            
+            let request = self.next.take()?;
+            let collection: AlertCollection = match self.client.fetch(&request) {
+                Ok(collection) => collection,
+                Err(err) => return Some(Err(err)),
+            };
+            
+            self.next = collection.next_page(&request);
+            self.page = collection.graph.into_iter();
+        }
+    }
+}
+
+/// The async counterpart of [`AlertIter`], created by `NwsAsyncClient::alerts_iter`. Call `next().await` until it
+/// returns `None`.
+#[cfg(feature = "async")]
+#[derive(Debug)]
+pub struct AsyncAlertIter<'a> {
+    client: &'a NwsAsyncClient,
+    next: Option<ApiRequest>,
+    page: std::vec::IntoIter<Alert>,
+}
+#[cfg(feature = "async")]
+impl<'a> AsyncAlertIter<'a> {
+    pub(crate) fn new(client: &'a NwsAsyncClient, first: ApiRequest) -> Self { Self {
+        client,
+        next: Some(first),
+        page: Vec::new().into_iter(),
+    }}
+    
+    /// The next alert, requesting the next page once the current one is used up.
+    pub async fn next(&mut self) -> Option<crate::Result<Alert>> {
+        loop {
+            if let Some(alert) = self.page.next() {
+                return Some(Ok(alert));
+            }
+            
+            let request = self.next.take()?;
+            let collection: AlertCollection = match self.client.fetch(&request).await {
+                Ok(collection) => collection,
+                Err(err) => return Some(Err(err)),
+            };
+            
+            self.next = collection.next_page(&request);
+            self.page = collection.graph.into_iter();
+        }
+    }
+}
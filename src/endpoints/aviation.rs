@@ -0,0 +1,137 @@
+use chrono::{DateTime, FixedOffset, Utc};
+use geo_types::Geometry;
+use serde::{Serialize, Deserialize};
+use url::Url;
+use super::{serialize_geometry_opt, deserialize_geometry_opt};
+
+
+/// The period during which an advisory or SIGMET is in effect.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ValidityWindow {
+    pub start: DateTime<FixedOffset>,
+    pub end: DateTime<FixedOffset>,
+}
+impl ValidityWindow {
+    /// Returns `true` if `time` falls within the window. The end is exclusive.
+    pub fn contains(&self, time: DateTime<Utc>) -> bool {
+        self.start <= time && time < self.end
+    }
+    
+    pub fn is_active(&self) -> bool {
+        self.contains(Utc::now())
+    }
+}
+
+/// A Center Weather Service Unit.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Cwsu {
+    pub id: String,
+    pub name: String,
+    pub street: Option<String>,
+    pub city: Option<String>,
+    pub state: Option<String>,
+    pub zip_code: Option<String>,
+    pub email: Option<String>,
+    pub fax: Option<String>,
+    pub phone: Option<String>,
+    pub url: Option<Url>,
+    pub nws_region: Option<String>,
+}
+
+/// A Center Weather Advisory.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Cwa {
+    /// Usually a polygon, but some advisories cover several areas or a line.
+    #[serde(default, deserialize_with = "deserialize_geometry_opt", serialize_with = "serialize_geometry_opt")]
+    pub geometry: Option<Geometry<f64>>,
+    pub id: String,
+    pub issue_time: DateTime<FixedOffset>,
+    pub cwsu: String,
+    pub sequence: usize,
+    #[serde(flatten)]
+    pub validity: ValidityWindow,
+    pub observed_property: String,
+    pub text: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CwaCollection {
+    #[serde(rename = "@graph")]
+    pub graph: Vec<Cwa>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Sigmet {
+    /// Usually a polygon, but some advisories cover several areas or a line.
+    #[serde(default, deserialize_with = "deserialize_geometry_opt", serialize_with = "serialize_geometry_opt")]
+    pub geometry: Option<Geometry<f64>>,
+    #[serde(rename = "@id")]
+    pub id: Url,
+    #[serde(rename = "id")]
+    pub identifier: String,
+    pub issue_time: DateTime<FixedOffset>,
+    /// Flight Information Region.
+    pub fir: Option<String>,
+    /// Air Traffic Service Unit.
+    pub atsu: String,
+    pub sequence: Option<String>,
+    pub phenomenon: Option<String>,
+    #[serde(flatten)]
+    pub validity: ValidityWindow,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SigmetCollection {
+    #[serde(rename = "@graph")]
+    pub graph: Vec<Sigmet>,
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn sigmets(geometry: &str) -> SigmetCollection {
+        serde_json::from_str(&format!(r#"{{"@graph": [{{
+            "geometry": {},
+            "@id": "https://api.weather.gov/aviation/sigmets/KKCI/2022-05-01/1855",
+            "id": "KKCI-2022-05-01-1855",
+            "issueTime": "2022-05-01T18:55:00+00:00",
+            "fir": null,
+            "atsu": "KKCI",
+            "sequence": "45C",
+            "phenomenon": null,
+            "start": "2022-05-01T18:55:00+00:00",
+            "end": "2022-05-01T20:55:00+00:00"
+        }}]}}"#, geometry)).unwrap()
+    }
+    
+    #[test]
+    fn parses_polygon_geometry() {
+        let collection = sigmets(r#""POLYGON((-97 35,-96 35,-96 36,-97 35))""#);
+        
+        assert!(matches!(collection.graph[0].geometry, Some(Geometry::Polygon(_))));
+    }
+    
+    #[test]
+    fn parses_multipolygon_geometry() {
+        let collection = sigmets(r#""MULTIPOLYGON(((-97 35,-96 35,-96 36,-97 35)),((-90 30,-89 30,-89 31,-90 30)))""#);
+        
+        match &collection.graph[0].geometry {
+            Some(Geometry::MultiPolygon(geom)) => assert_eq!(geom.0.len(), 2),
+            other => panic!("expected a multipolygon, got {:?}", other),
+        }
+        let round_trip: SigmetCollection = serde_json::from_value(serde_json::to_value(&collection).unwrap()).unwrap();
+        assert_eq!(round_trip.graph[0].geometry, collection.graph[0].geometry);
+    }
+    
+    #[test]
+    fn parses_missing_geometry() {
+        assert!(sigmets("null").graph[0].geometry.is_none());
+    }
+}
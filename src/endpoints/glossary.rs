@@ -0,0 +1,22 @@
+use std::collections::HashMap;
+use serde::{Serialize, Deserialize};
+
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GlossaryEntry {
+    pub term: Option<String>,
+    pub definition: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Glossary {
+    pub glossary: Vec<GlossaryEntry>,
+}
+impl Glossary {
+    /// Maps each term to its definition, skipping entries that are missing either.
+    pub fn into_map(self) -> HashMap<String, String> {
+        self.glossary.into_iter()
+            .filter_map(|entry| Some((entry.term?, entry.definition?)))
+            .collect()
+    }
+}
@@ -1,22 +1,173 @@
 
 
-use serde::{Serialize, Deserialize};
+use std::fmt::{Display, Formatter};
+use chrono::{DateTime, Duration, FixedOffset, Utc, MAX_DATETIME, MIN_DATETIME};
+use serde::{Serialize, Serializer, Deserialize, Deserializer};
+use serde_json::Value;
 use url::Url;
 use wkt::Wkt;
 use crate::endpoints::stations::ObservationStation;
+use crate::units::Unit;
+use crate::vtec::{Phenomenon, Significance};
 use super::{QuantitativeValue, serialize_wkt, deserialize_wkt, deserialize_force_usize};
 
 
 
+/// An ISO 8601 time interval such as `2022-05-01T12:00:00+00:00/PT3H`, as used for the `validTime` of gridpoint
+/// values.
+/// 
+/// The interval is half-open: it includes `start` but not `end()`, so consecutive values don't overlap.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ValidInterval {
+    pub start: DateTime<Utc>,
+    pub duration: Duration,
+}
+impl ValidInterval {
+    pub fn new(start: DateTime<Utc>, duration: Duration) -> Self { Self {
+        start,
+        duration,
+    }}
+    
+    /// Parses `start/duration`, `start/end` or `duration/end`. Intervals reaching past the dates `chrono` can represent
+    /// are rejected.
+    pub fn parse(value: impl AsRef<str>) -> Option<Self> {
+        let (first, second) = value.as_ref().trim().split_once('/')?;
+        let time = |time: &str| DateTime::parse_from_rfc3339(time).ok().map(|time| time.with_timezone(&Utc));
+        
+        if first.starts_with('P') {
+            let (duration, end) = (parse_duration(first)?, time(second)?);
+            Some(Self::new(end.checked_sub_signed(duration)?, duration))
+        } else if second.starts_with('P') {
+            let (start, duration) = (time(first)?, parse_duration(second)?);
+            start.checked_add_signed(duration)?;
+            Some(Self::new(start, duration))
+        } else {
+            let (start, end) = (time(first)?, time(second)?);
+            (end >= start).then(|| Self::new(start, end - start))
+        }
+    }
+    
+    /// The end of the interval, clamped to the dates `chrono` can represent.
+    pub fn end(&self) -> DateTime<Utc> {
+        match self.start.checked_add_signed(self.duration) {
+            Some(end) => end,
+            None if self.duration < Duration::zero() => MIN_DATETIME,
+            None => MAX_DATETIME,
+        }
+    }
+    
+    pub fn contains(&self, time: DateTime<Utc>) -> bool {
+        self.start <= time && time < self.end()
+    }
+    
+    /// Whether both intervals share some time. Intervals that merely touch don't overlap.
+    pub fn overlaps(&self, other: &ValidInterval) -> bool {
+        self.start < other.end() && other.start < self.end()
+    }
+}
+impl Display for ValidInterval {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.start.to_rfc3339(), format_duration(self.duration))
+    }
+}
+impl Serialize for ValidInterval {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        serializer.collect_str(self)
+    }
+}
+impl<'de> Deserialize<'de> for ValidInterval {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+        let s = String::deserialize(deserializer)?;
+        
+        ValidInterval::parse(&s).ok_or_else(|| serde::de::Error::custom(format!("Failed to parse ISO 8601 interval: {}", s)))
+    }
+}
+
+/// Parses an ISO 8601 duration such as `PT6H`, `P1DT2H` or `P1W`.
+/// 
+/// Years and months have no fixed length and are rejected, as are durations too long for [`Duration`].
+pub fn parse_duration(value: impl AsRef<str>) -> Option<Duration> {
+    let value = value.as_ref().strip_prefix('P')?;
+    let (date, time) = value.split_once('T').unwrap_or((value, ""));
+    if value.is_empty() || value.ends_with('T') {
+        return None;
+    }
+    
+    let mut seconds: i64 = 0;
+    for (part, is_time) in [(date, false), (time, true)] {
+        let mut number = String::new();
+        for c in part.chars() {
+            if c.is_ascii_digit() {
+                number.push(c);
+                continue;
+            }
+            let n: i64 = number.parse().ok()?;
+            number.clear();
+            let unit: i64 = match (c, is_time) {
+                ('W', false) => 7 * 86400,
+                ('D', false) => 86400,
+                ('H', true) => 3600,
+                ('M', true) => 60,
+                ('S', true) => 1,
+                _ => return None,
+            };
+            seconds = seconds.checked_add(n.checked_mul(unit)?)?;
+        }
+        if !number.is_empty() {
+            return None;
+        }
+    }
+    
+    // `Duration` counts milliseconds in an `i64`, and panics beyond that.
+    (seconds <= i64::MAX / 1000).then(|| Duration::seconds(seconds))
+}
+
+/// Formats a duration the way the API does, e.g. `P1DT2H`.
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.num_seconds();
+    let (days, hours, minutes, seconds) = (seconds / 86400, seconds % 86400 / 3600, seconds % 3600 / 60, seconds % 60);
+    
+    let mut formatted = String::from("P");
+    if days != 0 {
+        formatted += &format!("{}D", days);
+    }
+    if hours != 0 || minutes != 0 || seconds != 0 || days == 0 {
+        formatted.push('T');
+        if hours != 0 {
+            formatted += &format!("{}H", hours);
+        }
+        if minutes != 0 {
+            formatted += &format!("{}M", minutes);
+        }
+        if seconds != 0 || (hours == 0 && minutes == 0) {
+            formatted += &format!("{}S", seconds);
+        }
+    }
+    
+    formatted
+}
+
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Weather {
     pub values: Vec<WeatherValue>,
 }
+impl Weather {
+    /// The weather forecast for `time`, or `None` if no value covers it.
+    pub fn value_at(&self, time: DateTime<Utc>) -> Option<&[WeatherValueInner]> {
+        self.values.iter().find(|value| value.valid_time.contains(time)).map(|value| value.value.as_slice())
+    }
+    
+    /// The weather for every hour from `start`, rounded down to the hour, until `end`.
+    pub fn hourly(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Vec<(DateTime<Utc>, Option<&[WeatherValueInner]>)> {
+        hours(start, end).map(|time| (time, self.value_at(time))).collect()
+    }
+}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct WeatherValue {
-    pub valid_time: String,
+    pub valid_time: ValidInterval,
     pub value: Vec<WeatherValueInner>
 }
 
@@ -34,32 +185,78 @@ pub struct WeatherValueInner {
 pub struct Hazards {
     pub values: Vec<HazardsValue>,
 }
+impl Hazards {
+    /// The hazards in effect at `time`, or `None` if no value covers it.
+    pub fn value_at(&self, time: DateTime<Utc>) -> Option<&[HazardsValueInner]> {
+        self.values.iter().find(|value| value.valid_time.contains(time)).map(|value| value.value.as_slice())
+    }
+    
+    /// The hazards for every hour from `start`, rounded down to the hour, until `end`.
+    pub fn hourly(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Vec<(DateTime<Utc>, Option<&[HazardsValueInner]>)> {
+        hours(start, end).map(|time| (time, self.value_at(time))).collect()
+    }
+}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct HazardsValue {
-    pub valid_time: String,
+    pub valid_time: ValidInterval,
     pub value: Vec<HazardsValueInner>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct HazardsValueInner {
-    pub phenomenon: String,
-    pub significance: String,
+    pub phenomenon: Phenomenon,
+    pub significance: Significance,
     pub event_number: Option<f64>, // Unlike other structs this should *not* be renamed/aliased using camelCase
 }
 
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct QuantitativeValueLayer {
-    pub uom: Option<String>,
+    pub uom: Option<Unit>,
     pub values: Vec<QuantitativeValueLayerValue>,
 }
+impl QuantitativeValueLayer {
+    /// The value valid at `time`, or `None` if no value covers it or the value is null.
+    pub fn value_at(&self, time: DateTime<Utc>) -> Option<f64> {
+        self.values.iter().find(|value| value.valid_time.contains(time))?.value
+    }
+    
+    /// The value for every hour from `start`, rounded down to the hour, until `end`.
+    pub fn hourly(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Vec<(DateTime<Utc>, Option<f64>)> {
+        hours(start, end).map(|time| (time, self.value_at(time))).collect()
+    }
+    
+    /// Every value converted to `unit`, or `None` if the layer has no unit or it can't be converted.
+    pub fn to_unit(&self, unit: Unit) -> Option<QuantitativeValueLayer> {
+        let from = self.uom.as_ref()?;
+        from.convert(0.0, &unit)?;
+        let values = self.values.iter()
+            .map(|value| QuantitativeValueLayerValue {
+                valid_time: value.valid_time,
+                value: value.value.and_then(|value| from.convert(value, &unit)),
+            })
+            .collect();
+        
+        Some(QuantitativeValueLayer { uom: Some(unit), values })
+    }
+    
+    /// Converted to metric units, see [`Unit::si`]. Returns `self` unchanged if the layer has no known unit.
+    pub fn to_si(&self) -> QuantitativeValueLayer {
+        self.uom.as_ref().and_then(|unit| self.to_unit(unit.si())).unwrap_or_else(|| self.clone())
+    }
+    
+    /// Converted to US customary units, see [`Unit::us`]. Returns `self` unchanged if the layer has no known unit.
+    pub fn to_us(&self) -> QuantitativeValueLayer {
+        self.uom.as_ref().and_then(|unit| self.to_unit(unit.us())).unwrap_or_else(|| self.clone())
+    }
+}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct QuantitativeValueLayerValue {
-    pub valid_time: String,
+    pub valid_time: ValidInterval,
     pub value: Option<f64>,
 }
 
@@ -73,8 +270,8 @@ pub struct Gridpoint {
     pub id: Url,
     #[serde(rename = "@type")]
     pub kind: String,
-    pub update_time: String,
-    pub valid_times: String,
+    pub update_time: DateTime<FixedOffset>,
+    pub valid_times: ValidInterval,
     pub elevation: QuantitativeValue,
     pub forecast_office: Url,
     pub grid_id: String,
@@ -140,6 +337,116 @@ pub struct Gridpoint {
     pub stability: Option<QuantitativeValueLayer>,
     pub red_flag_thread_index: Option<QuantitativeValueLayer>,
 }
+impl Gridpoint {
+    /// Every layer present in the response, by its name in the API.
+    pub fn layers(&self) -> Vec<(&'static str, &QuantitativeValueLayer)> {
+        let layers = [
+            ("temperature", &self.temperature),
+            ("dewpoint", &self.dewpoint),
+            ("maxTemperature", &self.max_temperature),
+            ("minTemperature", &self.min_temperature),
+            ("relativeHumidity", &self.relative_humidity),
+            ("apparentTemperature", &self.apparent_temperature),
+            ("heatIndex", &self.heat_index),
+            ("windChill", &self.wind_chill),
+            ("skyCover", &self.sky_cover),
+            ("windDirection", &self.wind_direction),
+            ("windSpeed", &self.wind_speed),
+            ("windGust", &self.wind_gust),
+            ("probabilityOfPrecipitation", &self.probability_of_precipitation),
+            ("quantitativePrecipitation", &self.quantitative_precipitation),
+            ("iceAccumulation", &self.ice_accumulation),
+            ("snowfallAmount", &self.snowfall_amount),
+            ("snowLevel", &self.snow_level),
+            ("ceilingHeight", &self.ceiling_height),
+            ("visibility", &self.visibility),
+            ("transportWindSpeed", &self.transport_wind_speed),
+            ("transportWindDirection", &self.transport_wind_direction),
+            ("mixingHeight", &self.mixing_height),
+            ("hainesIndex", &self.haines_index),
+            ("lightningActivityLevel", &self.lightning_activity_level),
+            ("twentyFootWindSpeed", &self.twenty_foot_wind_speed),
+            ("twentyFootWindDirection", &self.twenty_foot_wind_direction),
+            ("waveHeight", &self.wave_height),
+            ("wavePeriod", &self.wave_period),
+            ("waveDirection", &self.wave_direction),
+            ("primarySwellHeight", &self.primary_swell_height),
+            ("primarySwellDirection", &self.primary_swell_direction),
+            ("secondarySwellHeight", &self.secondary_swell_height),
+            ("secondarySwellDirection", &self.secondary_swell_direction),
+            ("wavePeriod2", &self.wave_period2),
+            ("windWaveHeight", &self.wind_wave_height),
+            ("dispersionIndex", &self.dispersion_index),
+            ("pressure", &self.pressure),
+            ("probabilityOfTrapicalStormWinds", &self.probability_of_trapical_storm_winds),
+            ("probabilityOfHurricaneWinds", &self.probability_of_hurricane_winds),
+            ("potentialOf15mphWinds", &self.potential_of_15mph_winds),
+            ("potentialOf25mphWinds", &self.potential_of_25mph_winds),
+            ("potentialOf35mphWinds", &self.potential_of_35mph_winds),
+            ("potentialOf45mphWinds", &self.potential_of_45mph_winds),
+            ("potentialOf20mphWindGusts", &self.potential_of_20mph_wind_gusts),
+            ("potentialOf30mphWindGusts", &self.potential_of_30mph_wind_gusts),
+            ("potentialOf40mphWindGusts", &self.potential_of_40mph_wind_gusts),
+            ("potentialOf50mphWindGusts", &self.potential_of_50mph_wind_gusts),
+            ("potentialOf60mphWindGusts", &self.potential_of_60mph_wind_gusts),
+            ("grasslandFireDangerIndex", &self.grassland_fire_danger_index),
+            ("probabilityOfThunder", &self.probability_of_thunder),
+            ("davisStabilityIndex", &self.davis_stability_index),
+            ("atmosphericDispersionIndex", &self.atmospheric_dispersion_index),
+            ("stability", &self.stability),
+            ("redFlagThreadIndex", &self.red_flag_thread_index),
+        ];
+        
+        layers.into_iter().filter_map(|(name, layer)| Some((name, layer.as_ref()?))).collect()
+    }
+    
+    /// Lines up all layers, weather and hazards into one row per hour from `start`, rounded down to the hour, until
+    /// `end`. Hours a layer doesn't cover are `None`.
+    pub fn hourly_table(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> HourlyTable {
+        let layers = self.layers();
+        let columns = layers.iter().map(|(name, layer)| HourlyColumn { name: name.to_string(), unit: layer.uom.clone() }).collect();
+        let rows = hours(start, end)
+            .map(|time| HourlyRow {
+                time,
+                values: layers.iter().map(|(_, layer)| layer.value_at(time)).collect(),
+                weather: self.weather.as_ref().and_then(|weather| weather.value_at(time)).map(<[_]>::to_vec),
+                hazards: self.hazards.as_ref().and_then(|hazards| hazards.value_at(time)).map(<[_]>::to_vec),
+            })
+            .collect();
+        
+        HourlyTable { columns, rows }
+    }
+}
+
+/// Gridpoint layers sampled every hour, see [`Gridpoint::hourly_table`].
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HourlyTable {
+    pub columns: Vec<HourlyColumn>,
+    pub rows: Vec<HourlyRow>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct HourlyColumn {
+    /// Layer name as in the API, e.g. `skyCover`.
+    pub name: String,
+    pub unit: Option<Unit>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HourlyRow {
+    pub time: DateTime<Utc>,
+    /// One value per column, in the same order.
+    pub values: Vec<Option<f64>>,
+    pub weather: Option<Vec<WeatherValueInner>>,
+    pub hazards: Option<Vec<HazardsValueInner>>,
+}
+
+/// Every whole hour from `start`, rounded down, until `end`.
+fn hours(start: DateTime<Utc>, end: DateTime<Utc>) -> impl Iterator<Item = DateTime<Utc>> {
+    let first = start - Duration::seconds(start.timestamp().rem_euclid(3600)) - Duration::nanoseconds(start.timestamp_subsec_nanos() as i64);
+    
+    (0..).map_while(move |hour| first.checked_add_signed(Duration::hours(hour))).take_while(move |time| *time < end)
+}
 
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -151,9 +458,228 @@ pub struct GridpointStations {
 }
 
 
+/// Unit system used for the values of a textual forecast.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ForecastUnits {
+    Us,
+    Si,
+}
+impl Display for ForecastUnits {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            ForecastUnits::Us => "us",
+            ForecastUnits::Si => "si",
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TemperatureTrend {
+    Rising,
+    Falling,
+}
+
+/// A wind speed, or range of wind speeds, such as `"10 to 15 mph"`.
+/// 
+/// `low` and `high` are equal when the forecast gives a single speed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct WindSpeed {
+    pub low: f64,
+    pub high: f64,
+    pub unit: String,
+}
+impl WindSpeed {
+    /// Parses the textual form used by the forecast endpoints, e.g. `"5 mph"` or `"10 to 15 km/h"`.
+    pub fn parse(value: impl AsRef<str>) -> Option<Self> {
+        let parts: Vec<&str> = value.as_ref().split_whitespace().collect();
+        match parts.as_slice() {
+            [speed, unit] => {
+                let speed = speed.parse().ok()?;
+                Some(WindSpeed { low: speed, high: speed, unit: unit.to_string() })
+            },
+            [low, "to", high, unit] => Some(WindSpeed {
+                low: low.parse().ok()?,
+                high: high.parse().ok()?,
+                unit: unit.to_string(),
+            }),
+            _ => None
+        }
+    }
+    
+    fn from_quantitative_value(value: QuantitativeValue) -> Option<Self> {
+        let unit = value.unit_code.map(|unit| unit.code()).unwrap_or_default();
+        match (value.value, value.min_value, value.max_value) {
+            (Some(speed), _, _) => Some(WindSpeed { low: speed, high: speed, unit }),
+            (None, Some(low), Some(high)) => Some(WindSpeed { low, high, unit }),
+            _ => None
+        }
+    }
+}
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ForecastPeriod {
+    pub number: usize,
+    pub name: String,
+    pub start_time: DateTime<FixedOffset>,
+    pub end_time: DateTime<FixedOffset>,
+    pub is_daytime: bool,
+    #[serde(deserialize_with = "deserialize_temperature")]
+    pub temperature: f64,
+    #[serde(default)]
+    pub temperature_unit: Option<String>,
+    pub temperature_trend: Option<TemperatureTrend>,
+    pub probability_of_precipitation: Option<QuantitativeValue>,
+    pub dewpoint: Option<QuantitativeValue>,
+    pub relative_humidity: Option<QuantitativeValue>,
+    #[serde(deserialize_with = "deserialize_wind_speed")]
+    pub wind_speed: Option<WindSpeed>,
+    #[serde(default, deserialize_with = "deserialize_wind_speed")]
+    pub wind_gust: Option<WindSpeed>,
+    pub wind_direction: String,
+    pub icon: Url,
+    pub short_forecast: String,
+    pub detailed_forecast: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Forecast {
+    #[serde(deserialize_with = "deserialize_wkt", serialize_with = "serialize_wkt")]
+    pub geometry: Wkt<f64>,
+    pub updated: DateTime<FixedOffset>,
+    pub units: ForecastUnits,
+    pub forecast_generator: String,
+    pub generated_at: DateTime<FixedOffset>,
+    pub update_time: DateTime<FixedOffset>,
+    pub valid_times: ValidInterval,
+    pub elevation: QuantitativeValue,
+    pub periods: Vec<ForecastPeriod>,
+}
 
 
+/// Accepts either a plain number or, when the `forecast_temperature_qv` feature flag is set, a `QuantitativeValue`.
+fn deserialize_temperature<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+    match Value::deserialize(deserializer)? {
+        Value::Number(num) => match num.as_f64() {
+            Some(ok) => Ok(ok),
+            None => Err(serde::de::Error::custom("Failed to parse number into f64"))
+        },
+        value @ Value::Object(_) => match serde_json::from_value::<QuantitativeValue>(value) {
+            Ok(QuantitativeValue { value: Some(ok), .. }) => Ok(ok),
+            Ok(_) => Err(serde::de::Error::custom("Temperature QuantitativeValue has no value")),
+            Err(err) => Err(serde::de::Error::custom(err)),
+        },
+        _ => Err(serde::de::Error::custom("Failed to parse value into temperature"))
+    }
+}
 
+/// Accepts either the textual form (`"10 to 15 mph"`) or, when the `forecast_wind_speed_qv` feature flag is set, a
+/// `QuantitativeValue`.
+fn deserialize_wind_speed<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<WindSpeed>, D::Error> {
+    match Value::deserialize(deserializer)? {
+        Value::Null => Ok(None),
+        Value::String(s) if s.is_empty() => Ok(None),
+        Value::String(s) => match WindSpeed::parse(&s) {
+            Some(ok) => Ok(Some(ok)),
+            None => Err(serde::de::Error::custom(format!("Failed to parse wind speed: {}", s)))
+        },
+        value @ Value::Object(_) => match serde_json::from_value::<QuantitativeValue>(value) {
+            Ok(value) => Ok(WindSpeed::from_quantitative_value(value)),
+            Err(err) => Err(serde::de::Error::custom(err)),
+        },
+        _ => Err(serde::de::Error::custom("Failed to parse value into wind speed"))
+    }
+}
 
 
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use super::*;
+    
+    fn start() -> DateTime<Utc> {
+        Utc.ymd(2022, 5, 1).and_hms(12, 0, 0)
+    }
+    
+    #[test]
+    fn parses_interval_forms() {
+        let expected = ValidInterval::new(start(), Duration::hours(3));
+        assert_eq!(ValidInterval::parse("2022-05-01T12:00:00+00:00/PT3H"), Some(expected));
+        assert_eq!(ValidInterval::parse("2022-05-01T12:00:00+00:00/2022-05-01T15:00:00+00:00"), Some(expected));
+        assert_eq!(ValidInterval::parse("PT3H/2022-05-01T15:00:00+00:00"), Some(expected));
+        assert_eq!(ValidInterval::parse("2022-05-01T07:00:00-05:00/PT3H"), Some(expected));
+        assert_eq!(expected.end(), Utc.ymd(2022, 5, 1).and_hms(15, 0, 0));
+    }
+    
+    #[test]
+    fn rejects_malformed_intervals() {
+        assert_eq!(ValidInterval::parse("2022-05-01T12:00:00+00:00"), None);
+        assert_eq!(ValidInterval::parse("2022-05-01T12:00:00+00:00/P1M"), None);
+        assert_eq!(ValidInterval::parse("2022-05-01T15:00:00+00:00/2022-05-01T12:00:00+00:00"), None);
+        assert_eq!(ValidInterval::parse("yesterday/PT3H"), None);
+    }
+    
+    #[test]
+    fn rejects_intervals_out_of_range() {
+        assert_eq!(ValidInterval::parse("2022-05-01T12:00:00+00:00/P99999999999D"), None);
+        assert_eq!(ValidInterval::parse("P99999999999D/2022-05-01T12:00:00+00:00"), None);
+    }
+    
+    #[test]
+    fn clamps_end_of_huge_intervals() {
+        let interval = ValidInterval::new(start(), Duration::max_value());
+        assert_eq!(interval.end(), MAX_DATETIME);
+        assert!(interval.contains(start() + Duration::weeks(52)));
+        assert!(interval.overlaps(&ValidInterval::new(start(), Duration::hours(1))));
+    }
+    
+    #[test]
+    fn contains_is_half_open() {
+        let interval = ValidInterval::new(start(), Duration::hours(3));
+        assert!(interval.contains(start()));
+        assert!(interval.contains(start() + Duration::minutes(179)));
+        assert!(!interval.contains(start() + Duration::hours(3)));
+        assert!(!interval.overlaps(&ValidInterval::new(start() + Duration::hours(3), Duration::hours(1))));
+    }
+    
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("PT6H"), Some(Duration::hours(6)));
+        assert_eq!(parse_duration("P1DT2H30M15S"), Some(Duration::seconds(86400 + 2 * 3600 + 30 * 60 + 15)));
+        assert_eq!(parse_duration("P2W"), Some(Duration::weeks(2)));
+        assert_eq!(parse_duration("PT0S"), Some(Duration::zero()));
+    }
+    
+    #[test]
+    fn rejects_malformed_durations() {
+        for duration in ["", "P", "PT", "6H", "P1Y", "P1M", "PT1D", "P1H", "PT6", "PTH", "P1DT"] {
+            assert_eq!(parse_duration(duration), None, "{}", duration);
+        }
+    }
+    
+    #[test]
+    fn rejects_overflowing_durations() {
+        assert_eq!(parse_duration("P99999999999999999999W"), None);
+        assert_eq!(parse_duration("P9223372036854775807W"), None);
+        assert_eq!(parse_duration("PT9223372036854775807S"), None);
+        assert_eq!(parse_duration("P9223372036854775D"), None);
+        assert_eq!(parse_duration("PT9223372036854775S"), Some(Duration::seconds(i64::MAX / 1000)));
+    }
+    
+    #[test]
+    fn formats_durations_round_trip() {
+        for seconds in [0, 1, 59, 60, 3600, 3 * 3600, 86400, 86400 + 3600, 7 * 86400 + 90 * 60 + 5] {
+            let duration = Duration::seconds(seconds);
+            assert_eq!(parse_duration(format_duration(duration)), Some(duration), "{}", format_duration(duration));
+        }
+        assert_eq!(format_duration(Duration::hours(6)), "PT6H");
+        assert_eq!(format_duration(Duration::days(1) + Duration::hours(2)), "P1DT2H");
+        assert_eq!(format_duration(Duration::zero()), "PT0S");
+        
+        let interval = ValidInterval::new(start(), Duration::hours(3));
+        assert_eq!(ValidInterval::parse(interval.to_string()), Some(interval));
+    }
+}
@@ -0,0 +1,341 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use serde::{Serialize, Deserialize};
+use url::Url;
+
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum IconSet {
+    Land,
+    Marine,
+}
+impl Display for IconSet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            IconSet::Land => "land",
+            IconSet::Marine => "marine",
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeOfDay {
+    Day,
+    Night,
+}
+impl Display for TimeOfDay {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            TimeOfDay::Day => "day",
+            TimeOfDay::Night => "night",
+        })
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum IconSize {
+    Small,
+    Medium,
+    Large,
+    Pixels(u32),
+}
+impl Display for IconSize {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IconSize::Small => write!(f, "small"),
+            IconSize::Medium => write!(f, "medium"),
+            IconSize::Large => write!(f, "large"),
+            IconSize::Pixels(pixels) => write!(f, "{}", pixels),
+        }
+    }
+}
+
+/// Condition codes listed by the `/icons` endpoint. Codes not known to this crate are kept in `Other`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum IconCode {
+    Skc,
+    Few,
+    Sct,
+    Bkn,
+    Ovc,
+    WindSkc,
+    WindFew,
+    WindSct,
+    WindBkn,
+    WindOvc,
+    Snow,
+    RainSnow,
+    RainSleet,
+    SnowSleet,
+    Fzra,
+    RainFzra,
+    SnowFzra,
+    Sleet,
+    Rain,
+    RainShowers,
+    RainShowersHi,
+    Tsra,
+    TsraSct,
+    TsraHi,
+    Tornado,
+    Hurricane,
+    TropicalStorm,
+    Dust,
+    Smoke,
+    Haze,
+    Hot,
+    Cold,
+    Blizzard,
+    Fog,
+    Other(String),
+}
+impl IconCode {
+    pub fn from_code(code: &str) -> Self {
+        use IconCode::*;
+        match code {
+            "skc" => Skc,
+            "few" => Few,
+            "sct" => Sct,
+            "bkn" => Bkn,
+            "ovc" => Ovc,
+            "wind_skc" => WindSkc,
+            "wind_few" => WindFew,
+            "wind_sct" => WindSct,
+            "wind_bkn" => WindBkn,
+            "wind_ovc" => WindOvc,
+            "snow" => Snow,
+            "rain_snow" => RainSnow,
+            "rain_sleet" => RainSleet,
+            "snow_sleet" => SnowSleet,
+            "fzra" => Fzra,
+            "rain_fzra" => RainFzra,
+            "snow_fzra" => SnowFzra,
+            "sleet" => Sleet,
+            "rain" => Rain,
+            "rain_showers" => RainShowers,
+            "rain_showers_hi" => RainShowersHi,
+            "tsra" => Tsra,
+            "tsra_sct" => TsraSct,
+            "tsra_hi" => TsraHi,
+            "tornado" => Tornado,
+            "hurricane" => Hurricane,
+            "tropical_storm" => TropicalStorm,
+            "dust" => Dust,
+            "smoke" => Smoke,
+            "haze" => Haze,
+            "hot" => Hot,
+            "cold" => Cold,
+            "blizzard" => Blizzard,
+            "fog" => Fog,
+            _ => Other(code.to_owned()),
+        }
+    }
+    
+    pub fn code(&self) -> &str {
+        use IconCode::*;
+        match self {
+            Skc => "skc",
+            Few => "few",
+            Sct => "sct",
+            Bkn => "bkn",
+            Ovc => "ovc",
+            WindSkc => "wind_skc",
+            WindFew => "wind_few",
+            WindSct => "wind_sct",
+            WindBkn => "wind_bkn",
+            WindOvc => "wind_ovc",
+            Snow => "snow",
+            RainSnow => "rain_snow",
+            RainSleet => "rain_sleet",
+            SnowSleet => "snow_sleet",
+            Fzra => "fzra",
+            RainFzra => "rain_fzra",
+            SnowFzra => "snow_fzra",
+            Sleet => "sleet",
+            Rain => "rain",
+            RainShowers => "rain_showers",
+            RainShowersHi => "rain_showers_hi",
+            Tsra => "tsra",
+            TsraSct => "tsra_sct",
+            TsraHi => "tsra_hi",
+            Tornado => "tornado",
+            Hurricane => "hurricane",
+            TropicalStorm => "tropical_storm",
+            Dust => "dust",
+            Smoke => "smoke",
+            Haze => "haze",
+            Hot => "hot",
+            Cold => "cold",
+            Blizzard => "blizzard",
+            Fog => "fog",
+            Other(code) => code,
+        }
+    }
+}
+
+/// A single condition of an icon, e.g. `tsra,40` (thunderstorms, 40% probability).
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct IconCondition {
+    pub code: IconCode,
+    pub probability: Option<u8>,
+}
+impl IconCondition {
+    pub fn parse(value: impl AsRef<str>) -> Option<Self> {
+        let value = value.as_ref();
+        if value.is_empty() {
+            return None;
+        }
+        
+        match value.split_once(',') {
+            Some((code, probability)) => Some(IconCondition {
+                code: IconCode::from_code(code),
+                probability: Some(probability.parse().ok()?),
+            }),
+            None => Some(IconCondition { code: IconCode::from_code(value), probability: None }),
+        }
+    }
+}
+impl Display for IconCondition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.probability {
+            Some(probability) => write!(f, "{},{}", self.code.code(), probability),
+            None => write!(f, "{}", self.code.code()),
+        }
+    }
+}
+
+/// The components of an icon URL, such as `/icons/land/day/tsra,40/sct?size=medium`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Icon {
+    pub set: IconSet,
+    pub time_of_day: TimeOfDay,
+    /// One condition, or two for dual-condition icons.
+    pub conditions: Vec<IconCondition>,
+    pub size: Option<IconSize>,
+}
+impl Icon {
+    /// Parses an absolute icon URL, or just its path and query.
+    pub fn parse(url: impl AsRef<str>) -> Option<Self> {
+        let url = url.as_ref();
+        let (path, query) = match url.split_once('?') {
+            Some((path, query)) => (path, Some(query)),
+            None => (url, None),
+        };
+        
+        let mut segments = path.split('/').skip_while(|segment| *segment != "icons").skip(1);
+        let set = match segments.next()? {
+            "land" => IconSet::Land,
+            "marine" => IconSet::Marine,
+            _ => return None
+        };
+        let time_of_day = match segments.next()? {
+            "day" => TimeOfDay::Day,
+            "night" => TimeOfDay::Night,
+            _ => return None
+        };
+        let conditions = segments.map(IconCondition::parse).collect::<Option<Vec<IconCondition>>>()?;
+        if conditions.is_empty() || conditions.len() > 2 {
+            return None;
+        }
+        
+        let size = query.and_then(|query| {
+            query.split('&').find_map(|pair| match pair.split_once('=') {
+                Some(("size", "small")) => Some(IconSize::Small),
+                Some(("size", "medium")) => Some(IconSize::Medium),
+                Some(("size", "large")) => Some(IconSize::Large),
+                Some(("size", pixels)) => pixels.parse().ok().map(IconSize::Pixels),
+                _ => None
+            })
+        });
+        
+        Some(Icon {
+            set,
+            time_of_day,
+            conditions,
+            size,
+        })
+    }
+    
+    pub fn from_url(url: &Url) -> Option<Self> {
+        Icon::parse(url.as_str())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct IconDescription {
+    pub description: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct IconCollection {
+    /// Descriptions keyed by condition code.
+    pub icons: HashMap<String, IconDescription>,
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn condition(code: IconCode, probability: Option<u8>) -> IconCondition {
+        IconCondition { code, probability }
+    }
+    
+    #[test]
+    fn parses_icon_path() {
+        assert_eq!(Icon::parse("/icons/land/day/tsra,40/sct?size=medium"), Some(Icon {
+            set: IconSet::Land,
+            time_of_day: TimeOfDay::Day,
+            conditions: vec![condition(IconCode::Tsra, Some(40)), condition(IconCode::Sct, None)],
+            size: Some(IconSize::Medium),
+        }));
+    }
+    
+    #[test]
+    fn parses_absolute_url() {
+        let url = Url::parse("https://api.weather.gov/icons/marine/night/rain_showers,20?size=small").unwrap();
+        let icon = Icon::from_url(&url).unwrap();
+        
+        assert_eq!((icon.set, icon.time_of_day), (IconSet::Marine, TimeOfDay::Night));
+        assert_eq!(icon.conditions, [condition(IconCode::RainShowers, Some(20))]);
+        assert_eq!(icon.size, Some(IconSize::Small));
+    }
+    
+    #[test]
+    fn parses_conditions() {
+        let icon = Icon::parse("https://api.weather.gov/icons/land/night/snow,60/wind_bkn").unwrap();
+        assert_eq!(icon.conditions, [condition(IconCode::Snow, Some(60)), condition(IconCode::WindBkn, None)]);
+        assert_eq!(icon.size, None);
+        
+        assert_eq!(IconCondition::parse("novel_code").map(|condition| condition.code), Some(IconCode::Other("novel_code".to_owned())));
+        assert_eq!(condition(IconCode::Tsra, Some(40)).to_string(), "tsra,40");
+    }
+    
+    #[test]
+    fn parses_pixel_size() {
+        let icon = Icon::parse("/icons/land/day/skc?fontsize=12&size=128").unwrap();
+        
+        assert_eq!(icon.size, Some(IconSize::Pixels(128)));
+        assert_eq!(icon.size.unwrap().to_string(), "128");
+    }
+    
+    #[test]
+    fn rejects_malformed_icons() {
+        for url in [
+            "/icons/air/day/skc",
+            "/icons/land/dusk/skc",
+            "/icons/land/day",
+            "/icons/land/day/tsra,40/sct/rain",
+            "/icons/land/day/tsra,forty",
+            "/icons/land/day/tsra,",
+            "/icons/land/day/tsra,400",
+            "/land/day/skc",
+        ] {
+            assert_eq!(Icon::parse(url), None, "{}", url);
+        }
+        assert_eq!(IconCondition::parse("rain,x"), None);
+        assert_eq!(IconCondition::parse(""), None);
+    }
+}
@@ -1,24 +1,65 @@
 
 
+use std::convert::TryFrom;
+use geo_types::{Geometry, MultiPolygon};
 use serde::{Serialize, Serializer, Deserialize, Deserializer};
 use serde_json::Value;
-use wkt::Wkt;
+use wkt::{ToWkt, Wkt};
+use crate::units::Unit;
 
+pub mod alerts;
+pub mod aviation;
+pub mod glossary;
 pub mod gridpoints;
+pub mod icons;
+pub mod offices;
 pub mod points;
+pub mod products;
 pub mod radar;
 pub mod stations;
+pub mod zones;
 
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct QuantitativeValue {
-    pub unit_code: Option<String>,
+    pub unit_code: Option<Unit>,
     pub value: Option<f64>,
     pub max_value: Option<f64>,
     pub min_value: Option<f64>,
     pub quality_control: Option<String>
 }
+impl QuantitativeValue {
+    /// The value converted to `unit`, or `None` if there is no value or it can't be converted.
+    pub fn value_in(&self, unit: Unit) -> Option<f64> {
+        self.unit_code.as_ref()?.convert(self.value?, &unit)
+    }
+    
+    /// The value, minimum and maximum converted to `unit`, or `None` if there is no unit or it can't be converted.
+    pub fn to_unit(&self, unit: Unit) -> Option<QuantitativeValue> {
+        let from = self.unit_code.as_ref()?;
+        from.convert(0.0, &unit)?;
+        let convert = |value: Option<f64>| value.and_then(|value| from.convert(value, &unit));
+        
+        Some(QuantitativeValue {
+            value: convert(self.value),
+            max_value: convert(self.max_value),
+            min_value: convert(self.min_value),
+            quality_control: self.quality_control.clone(),
+            unit_code: Some(unit),
+        })
+    }
+    
+    /// Converted to metric units, see [`Unit::si`]. Returns `self` unchanged if there is no known unit.
+    pub fn to_si(&self) -> QuantitativeValue {
+        self.unit_code.as_ref().and_then(|unit| self.to_unit(unit.si())).unwrap_or_else(|| self.clone())
+    }
+    
+    /// Converted to US customary units, see [`Unit::us`]. Returns `self` unchanged if there is no known unit.
+    pub fn to_us(&self) -> QuantitativeValue {
+        self.unit_code.as_ref().and_then(|unit| self.to_unit(unit.us())).unwrap_or_else(|| self.clone())
+    }
+}
 
 
 fn serialize_wkt<S>(wkt: &Wkt<f64>, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
@@ -39,6 +80,72 @@ fn deserialize_wkt<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Wkt<f64
     }
 }
 
+fn serialize_wkt_opt<S>(wkt: &Option<Wkt<f64>>, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+    let s = wkt.as_ref().and_then(|wkt| wkt.items.first()).map(|geom| geom.to_string());
+    
+    s.serialize(serializer)
+}
+
+fn deserialize_wkt_opt<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Wkt<f64>>, D::Error> {
+    match Option::<String>::deserialize(deserializer)? {
+        Some(buf) => match Wkt::from_str(&buf) {
+            Ok(wkt) => Ok(Some(wkt)),
+            Err(_) => Err(serde::de::Error::custom("Invalid WKT geometry string")),
+        },
+        None => Ok(None),
+    }
+}
+
+fn serialize_multipolygon_opt<S>(geom: &Option<MultiPolygon<f64>>, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+    let s = geom.as_ref().and_then(|geom| Geometry::MultiPolygon(geom.clone()).to_wkt().items.first().map(|geom| geom.to_string()));
+    
+    s.serialize(serializer)
+}
+
+/// Parses an optional WKT string into a multipolygon, promoting a single polygon and gathering the polygons of a
+/// geometry collection. Anything else, such as an empty or unparsable geometry, becomes `None` rather than an error,
+/// so one odd zone doesn't fail a whole collection.
+fn deserialize_multipolygon_opt<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<MultiPolygon<f64>>, D::Error> {
+    let buf = match Option::<String>::deserialize(deserializer)? {
+        Some(buf) => buf,
+        None => return Ok(None),
+    };
+    
+    let geom = Wkt::from_str(&buf).ok().and_then(|wkt| Geometry::try_from(wkt).ok());
+    let polygons = geom.and_then(polygons).map(|geom| geom.0).unwrap_or_default();
+    let polygons: Vec<_> = polygons.into_iter().filter(|polygon| !polygon.exterior().0.is_empty()).collect();
+    
+    Ok((!polygons.is_empty()).then_some(MultiPolygon(polygons)))
+}
+
+fn polygons(geom: Geometry<f64>) -> Option<MultiPolygon<f64>> {
+    match geom {
+        Geometry::MultiPolygon(geom) => Some(geom),
+        Geometry::Polygon(geom) => Some(MultiPolygon(vec![geom])),
+        Geometry::GeometryCollection(collection) => {
+            Some(MultiPolygon(collection.into_iter().filter_map(polygons).flat_map(|geom| geom.0).collect()))
+        }
+        _ => None,
+    }
+}
+
+fn serialize_geometry_opt<S>(geom: &Option<Geometry<f64>>, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+    let s = geom.as_ref().and_then(|geom| geom.to_wkt().items.first().map(|geom| geom.to_string()));
+    
+    s.serialize(serializer)
+}
+
+/// Parses an optional WKT string of any geometry type. Geometries that can't be represented, such as empty ones,
+/// become `None`.
+fn deserialize_geometry_opt<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Geometry<f64>>, D::Error> {
+    let wkt = match deserialize_wkt_opt(deserializer)? {
+        Some(wkt) => wkt,
+        None => return Ok(None),
+    };
+    
+    Ok(Geometry::try_from(wkt).ok())
+}
+
 fn deserialize_force_usize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<usize, D::Error> {
     match Value::deserialize(deserializer) {
         Ok(ok) => match ok {
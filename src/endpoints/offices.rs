@@ -0,0 +1,103 @@
+use chrono::{DateTime, FixedOffset};
+use serde::{Serialize, Deserialize};
+use url::Url;
+
+
+/// Identifiers of every NWS Weather Forecast Office, as used by `/offices/{officeId}`.
+/// 
+/// Some forecast grids are not named after an office; see [`is_grid_id`] for the IDs `/gridpoints` accepts.
+pub const WFO_IDS: &[&str] = &[
+    // Eastern Region
+    "AKQ", "ALY", "BGM", "BOX", "BTV", "BUF", "CAE", "CAR", "CHS", "CLE", "CTP", "GSP", "GYX", "ILM", "ILN", "LWX",
+    "MHX", "OKX", "PBZ", "PHI", "RAH", "RLX", "RNK",
+    // Central Region
+    "ABR", "APX", "ARX", "BIS", "BOU", "CYS", "DDC", "DLH", "DMX", "DTX", "DVN", "EAX", "FGF", "FSD", "GID", "GJT",
+    "GLD", "GRB", "GRR", "ICT", "ILX", "IND", "IWX", "JKL", "LBF", "LMK", "LOT", "LSX", "MKX", "MPX", "MQT", "OAX",
+    "PAH", "PUB", "RIW", "SGF", "TOP", "UNR",
+    // Southern Region
+    "ABQ", "AMA", "BMX", "BRO", "CRP", "EPZ", "EWX", "FFC", "FWD", "HGX", "HUN", "JAN", "JAX", "KEY", "LCH", "LIX",
+    "LUB", "LZK", "MAF", "MEG", "MFL", "MLB", "MOB", "MRX", "OHX", "OUN", "SHV", "SJT", "SJU", "TAE", "TBW", "TSA",
+    // Western Region
+    "BOI", "BYZ", "EKA", "FGZ", "GGW", "HNX", "LKN", "LOX", "MFR", "MSO", "MTR", "OTX", "PDT", "PIH", "PQR", "PSR",
+    "REV", "SEW", "SGX", "SLC", "STO", "TFX", "TWC", "VEF",
+    // Alaska Region
+    "AFC", "AFG", "AJK",
+    // Pacific Region
+    "HFO", "GUM", "PPG",
+];
+
+/// Grid IDs of `/gridpoints/{wfo}/{x},{y}` that are not forecast offices: the Alaska sub-grids of AFC and the
+/// offshore/marine grids.
+pub const EXTRA_GRID_IDS: &[&str] = &["AER", "ALU", "HPA", "STU", "NH1", "NH2", "ONA", "ONP"];
+
+/// Returns `true` if `id` is a known forecast office identifier. The comparison is case-insensitive.
+pub fn is_wfo(id: &str) -> bool {
+    WFO_IDS.iter().any(|wfo| wfo.eq_ignore_ascii_case(id))
+}
+
+/// Returns `true` if `id` is a grid ID accepted by `/gridpoints`: an office or one of [`EXTRA_GRID_IDS`]. The
+/// comparison is case-insensitive.
+pub fn is_grid_id(id: &str) -> bool {
+    is_wfo(id) || EXTRA_GRID_IDS.iter().any(|grid| grid.eq_ignore_ascii_case(id))
+}
+
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OfficeAddress {
+    pub street_address: String,
+    pub address_locality: String,
+    pub address_region: String,
+    pub postal_code: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Office {
+    #[serde(rename = "@id")]
+    pub id: Url,
+    #[serde(rename = "@type")]
+    pub kind: String,
+    #[serde(rename = "id")]
+    pub identifier: String,
+    pub name: String,
+    pub address: OfficeAddress,
+    pub telephone: Option<String>,
+    pub fax_number: Option<String>,
+    pub email: Option<String>,
+    pub same_as: Option<Url>,
+    pub nws_region: Option<String>,
+    pub parent_organization: Option<Url>,
+    #[serde(default)]
+    pub responsible_counties: Vec<Url>,
+    #[serde(default)]
+    pub responsible_forecast_zones: Vec<Url>,
+    #[serde(default)]
+    pub responsible_fire_zones: Vec<Url>,
+    #[serde(default)]
+    pub approved_observation_stations: Vec<Url>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OfficeHeadline {
+    #[serde(rename = "@id")]
+    pub id: Url,
+    #[serde(rename = "id")]
+    pub identifier: String,
+    pub office: Url,
+    pub important: bool,
+    pub issuance_time: DateTime<FixedOffset>,
+    pub link: Option<Url>,
+    pub name: String,
+    pub title: String,
+    pub summary: Option<String>,
+    pub content: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OfficeHeadlineCollection {
+    #[serde(rename = "@graph")]
+    pub graph: Vec<OfficeHeadline>,
+}
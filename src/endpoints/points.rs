@@ -1,8 +1,10 @@
 
 
+use chrono_tz::Tz;
 use serde::{Serialize, Deserialize};
 use url::Url;
 use wkt::Wkt;
+use crate::cache::Lru;
 use super::{QuantitativeValue, serialize_wkt, deserialize_wkt, deserialize_force_usize};
 
 
@@ -41,6 +43,41 @@ pub struct Point {
     pub forecast_zone: Url,
     pub county: Url,
     pub fire_weather_zone: Url,
-    pub time_zone: String,
+    pub time_zone: Tz,
     pub radar_station: String,
-}
\ No newline at end of file
+}
+
+
+/// `/points` lookups made by the `*_at` client helpers, keyed by coordinates at the precision sent to the API. Only
+/// the most recently used points are kept.
+#[derive(Debug)]
+pub(crate) struct PointCache(Lru<Point>);
+impl PointCache {
+    /// How many points a client remembers unless configured otherwise.
+    pub const DEFAULT_CAPACITY: usize = 1024;
+    
+    pub fn new(capacity: usize) -> Self {
+        Self(Lru::new(capacity))
+    }
+    
+    fn key(lat: f64, lon: f64) -> String {
+        format!("{:.4},{:.4}", lat, lon)
+    }
+    
+    pub fn get(&self, lat: f64, lon: f64) -> Option<Point> {
+        self.0.get(&Self::key(lat, lon))
+    }
+    
+    pub fn insert(&self, lat: f64, lon: f64, point: Point) {
+        self.0.insert(&Self::key(lat, lon), point);
+    }
+    
+    pub fn clear(&self) {
+        self.0.clear();
+    }
+}
+impl Default for PointCache {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CAPACITY)
+    }
+}
@@ -0,0 +1,103 @@
+use std::cmp::{max, min};
+use std::collections::HashMap;
+use chrono::{DateTime, FixedOffset, Utc};
+use serde::{Serialize, Deserialize};
+use url::Url;
+use crate::DATETIME_FMT;
+use self::text::TextProduct;
+
+pub mod text;
+
+
+/// A text product as listed by the product collection endpoints, without its text body.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProductSummary {
+    #[serde(rename = "@id")]
+    pub id: Url,
+    #[serde(rename = "id")]
+    pub identifier: String,
+    pub wmo_collective_id: String,
+    pub issuing_office: String,
+    pub issuance_time: DateTime<FixedOffset>,
+    pub product_code: String,
+    pub product_name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Product {
+    #[serde(rename = "@id")]
+    pub id: Url,
+    #[serde(rename = "id")]
+    pub identifier: String,
+    pub wmo_collective_id: String,
+    pub issuing_office: String,
+    pub issuance_time: DateTime<FixedOffset>,
+    pub product_code: String,
+    pub product_name: String,
+    pub product_text: String,
+}
+impl Product {
+    /// Parses `product_text` into its heading, segments and sections. See [`TextProduct::parse`].
+    pub fn parse_text(&self) -> Option<TextProduct> {
+        TextProduct::parse(&self.product_text)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProductCollection {
+    #[serde(rename = "@graph")]
+    pub graph: Vec<ProductSummary>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProductType {
+    pub product_code: String,
+    pub product_name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProductTypeCollection {
+    #[serde(rename = "@graph")]
+    pub graph: Vec<ProductType>,
+}
+
+/// Product issuance locations, keyed by location ID. Not every location has a name.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProductLocations {
+    pub locations: HashMap<String, Option<String>>,
+}
+
+
+/// Query parameters accepted by the `/products` endpoint.
+/// 
+/// Empty lists and `None` values are omitted from the request.
+#[derive(Clone, Debug, Default)]
+pub struct ProductFilter {
+    pub location: Vec<String>,
+    pub start: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
+    pub office: Vec<String>,
+    pub wmoid: Vec<String>,
+    pub product_type: Vec<String>,
+    pub limit: Option<usize>,
+}
+impl ProductFilter {
+    pub(crate) fn params(&self) -> Vec<(&'static str, String)> {
+        let mut params = Vec::new();
+        if !self.location.is_empty() { params.push(("location", self.location.join(","))); }
+        if let Some(start) = self.start { params.push(("start", start.format(DATETIME_FMT).to_string())); }
+        if let Some(end) = self.end { params.push(("end", end.format(DATETIME_FMT).to_string())); }
+        if !self.office.is_empty() { params.push(("office", self.office.join(","))); }
+        if !self.wmoid.is_empty() { params.push(("wmoid", self.wmoid.join(","))); }
+        if !self.product_type.is_empty() { params.push(("type", self.product_type.join(","))); }
+        if let Some(limit) = self.limit { params.push(("limit", max(1, min(500, limit)).to_string())); }
+        
+        params
+    }
+}
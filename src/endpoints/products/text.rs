@@ -0,0 +1,389 @@
+//! Parser for the structure of NWS text products, such as the `productText` of a [`Product`](super::Product).
+//! 
+//! Parsing is done entirely on the raw text, so captured products can be parsed without access to the API.
+
+use serde::{Serialize, Deserialize};
+use crate::vtec::{Ugc, Vtec};
+
+
+/// The WMO abbreviated heading that starts every product, e.g. `FXUS61 KLWX 011918` or `WWUS41 KLWX 011918 CCA`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct WmoHeading {
+    /// Data type designator and number (`TTAAii`).
+    pub designator: String,
+    /// Originating office (`CCCC`).
+    pub office: String,
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+    /// Optional amendment, correction or delay indicator (`BBB`).
+    pub bbb: Option<String>,
+}
+impl WmoHeading {
+    pub fn parse(line: impl AsRef<str>) -> Option<Self> {
+        let parts: Vec<&str> = line.as_ref().split_whitespace().collect();
+        let (designator, office, time, bbb) = match parts.as_slice() {
+            [designator, office, time] => (designator, office, time, None),
+            [designator, office, time, bbb] => (designator, office, time, Some(bbb)),
+            _ => return None
+        };
+        
+        let valid_designator = designator.len() == 6
+            && designator.is_ascii()
+            && designator[..4].chars().all(|c| c.is_ascii_uppercase())
+            && designator[4..].chars().all(|c| c.is_ascii_digit());
+        let valid_office = office.len() == 4 && office.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit());
+        let valid_time = time.len() == 6 && time.chars().all(|c| c.is_ascii_digit());
+        let valid_bbb = bbb.is_none_or(|bbb| bbb.len() == 3 && bbb.chars().all(|c| c.is_ascii_uppercase()));
+        if !(valid_designator && valid_office && valid_time && valid_bbb) {
+            return None;
+        }
+        
+        Some(WmoHeading {
+            designator: designator.to_string(),
+            office: office.to_string(),
+            day: time[0..2].parse().ok()?,
+            hour: time[2..4].parse().ok()?,
+            minute: time[4..6].parse().ok()?,
+            bbb: bbb.map(|bbb| bbb.to_string()),
+        })
+    }
+}
+
+/// A block of product text. Titled sections start with a line such as `.SYNOPSIS...` and typically end at `&&`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TextSection {
+    pub title: Option<String>,
+    pub body: String,
+}
+
+/// A product segment, delimited by `$$`. Segments of a segmented product start with UGC and VTEC lines.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TextSegment {
+    /// The UGC string, with continuation lines joined, e.g. `MDZ003-VAZ052>055-057-020000-`.
+    pub ugc: Option<String>,
+    /// Raw VTEC strings, e.g. `/O.NEW.KLWX.WS.W.0003.220101T1200Z-220102T0000Z/`.
+    pub vtec: Vec<String>,
+    pub sections: Vec<TextSection>,
+}
+impl TextSegment {
+    /// Parses the segment's UGC string, if it has one.
+    pub fn parse_ugc(&self) -> Option<Ugc> {
+        self.ugc.as_ref().and_then(Ugc::parse)
+    }
+    
+    /// Parses the segment's VTEC strings, skipping any that are malformed.
+    pub fn parse_vtec(&self) -> Vec<Vtec> {
+        self.vtec.iter().filter_map(Vtec::parse).collect()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TextProduct {
+    pub heading: WmoHeading,
+    /// The AWIPS product identifier line (PIL), e.g. `AFDLWX`.
+    pub awips_id: Option<String>,
+    pub product_name: Option<String>,
+    pub issuing_office: Option<String>,
+    /// The issuance line, e.g. `318 PM EDT Sun May 1 2022`.
+    pub issuance: Option<String>,
+    pub segments: Vec<TextSegment>,
+}
+impl TextProduct {
+    /// Parses raw product text. Returns `None` if the text does not start with a WMO abbreviated heading (optionally
+    /// preceded by a sequence number line such as `000`).
+    pub fn parse(text: impl AsRef<str>) -> Option<Self> {
+        let mut lines = text.as_ref().lines().map(|line| line.trim_end()).peekable();
+        
+        while let Some(line) = lines.peek() {
+            if line.is_empty() || (line.len() == 3 && line.chars().all(|c| c.is_ascii_digit())) {
+                lines.next();
+            } else {
+                break;
+            }
+        }
+        let heading = WmoHeading::parse(lines.next()?)?;
+        
+        let awips_id = match lines.peek() {
+            Some(line) if is_awips_id(line) => lines.next().map(|line| line.to_string()),
+            _ => None
+        };
+        
+        while let Some(line) = lines.peek() {
+            if line.is_empty() { lines.next(); } else { break; }
+        }
+        let rest: Vec<&str> = lines.collect();
+        
+        let mut product_name = None;
+        let mut issuing_office = None;
+        let mut issuance = None;
+        let block_len = rest.iter().position(|line| line.is_empty()).unwrap_or(rest.len());
+        let header_len = match rest[..block_len].iter().position(|line| is_issuance_line(line)) {
+            Some(pos) if !rest[..pos].iter().any(|line| is_ugc_line(line)) => {
+                product_name = rest[..pos].first().map(|line| line.to_string());
+                issuing_office = rest[..pos].get(1).map(|line| line.to_string());
+                issuance = Some(rest[pos].to_string());
+                pos + 1
+            },
+            _ => 0
+        };
+        
+        let segments = rest[header_len..]
+            .split(|line| *line == "$$")
+            .filter_map(parse_segment)
+            .collect();
+        
+        Some(TextProduct {
+            heading,
+            awips_id,
+            product_name,
+            issuing_office,
+            issuance,
+            segments,
+        })
+    }
+    
+    /// Finds the first section with the given title, ignoring case, across all segments.
+    pub fn section(&self, title: &str) -> Option<&TextSection> {
+        self.segments.iter()
+            .flat_map(|segment| segment.sections.iter())
+            .find(|section| section.title.as_deref().is_some_and(|t| t.eq_ignore_ascii_case(title)))
+    }
+}
+
+
+fn parse_segment(lines: &[&str]) -> Option<TextSegment> {
+    let mut lines = lines.iter().copied().skip_while(|line| line.is_empty()).peekable();
+    lines.peek()?;
+    
+    let mut ugc = None;
+    while let Some(line) = lines.peek() {
+        if !(is_ugc_line(line) || (ugc.is_some() && is_ugc_continuation(line))) {
+            break;
+        }
+        ugc.get_or_insert_with(String::new).push_str(line);
+        lines.next();
+    }
+    
+    let mut vtec = vec![];
+    while let Some(line) = lines.peek() {
+        if !is_vtec_line(line) {
+            break;
+        }
+        vtec.push(line.to_string());
+        lines.next();
+    }
+    
+    let mut sections = vec![];
+    let mut title = None;
+    let mut body: Vec<&str> = vec![];
+    for line in lines {
+        if line == "&&" {
+            push_section(&mut sections, title.take(), &body);
+            body.clear();
+        } else if let Some((header, first)) = section_header(line) {
+            push_section(&mut sections, title.take(), &body);
+            body.clear();
+            title = Some(header.to_string());
+            if !first.is_empty() {
+                body.push(first);
+            }
+        } else {
+            body.push(line);
+        }
+    }
+    push_section(&mut sections, title, &body);
+    
+    if ugc.is_none() && vtec.is_empty() && sections.is_empty() {
+        return None;
+    }
+    
+    Some(TextSegment {
+        ugc,
+        vtec,
+        sections,
+    })
+}
+
+fn push_section(sections: &mut Vec<TextSection>, title: Option<String>, body: &[&str]) {
+    let body = body.join("\n").trim_matches('\n').to_string();
+    if title.is_some() || !body.trim().is_empty() {
+        sections.push(TextSection { title, body });
+    }
+}
+
+/// Splits a line like `.NEAR TERM /THROUGH TONIGHT/...Rain ends` into its title and the text following it.
+fn section_header(line: &str) -> Option<(&str, &str)> {
+    let rest = line.strip_prefix('.')?;
+    if !rest.starts_with(|c: char| c.is_ascii_uppercase()) {
+        return None;
+    }
+    let end = rest.find("...")?;
+    
+    Some((rest[..end].trim(), rest[end + 3..].trim()))
+}
+
+fn is_awips_id(line: &str) -> bool {
+    (4..=6).contains(&line.len()) && line.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+}
+
+/// Matches lines such as `318 PM EDT Sun May 1 2022`.
+fn is_issuance_line(line: &str) -> bool {
+    let mut parts = line.split_whitespace();
+    match (parts.next(), parts.next()) {
+        (Some(time), Some(meridiem)) => {
+            (1..=4).contains(&time.len())
+                && time.chars().all(|c| c.is_ascii_digit())
+                && (meridiem == "AM" || meridiem == "PM")
+        },
+        _ => false
+    }
+}
+
+/// Matches the first line of a UGC string, e.g. `VAZ052>055-057-011200-`.
+pub(crate) fn is_ugc_line(line: &str) -> bool {
+    let bytes = line.as_bytes();
+    bytes.len() >= 7
+        && bytes[..2].iter().all(|b| b.is_ascii_uppercase())
+        && (bytes[2] == b'Z' || bytes[2] == b'C')
+        && bytes[3..6].iter().all(|b| b.is_ascii_digit())
+        && (bytes[6] == b'-' || bytes[6] == b'>')
+        && is_ugc_continuation(line)
+}
+
+fn is_ugc_continuation(line: &str) -> bool {
+    line.ends_with('-') && line.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '-' || c == '>')
+}
+
+fn is_vtec_line(line: &str) -> bool {
+    line.len() > 2 && line.starts_with('/') && line.ends_with('/')
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    const AFD: &str = "000
+FXUS61 KLWX 011918
+AFDLWX
+
+Area Forecast Discussion
+National Weather Service Baltimore MD/Washington DC
+318 PM EDT Sun May 1 2022
+
+.SYNOPSIS...
+High pressure will build overhead tonight.
+A cold front approaches Tuesday.
+&&
+
+.NEAR TERM /THROUGH TONIGHT/...Clouds clear out this evening.
+Lows in the 40s.
+&&
+
+$$
+
+SYNOPSIS...KLW
+";
+    
+    const WSW: &str = "000
+WWUS41 KLWX 011918 CCA
+WSWLWX
+
+URGENT - WINTER WEATHER MESSAGE
+National Weather Service Baltimore MD/Washington DC
+218 PM EST Sat Jan 1 2022
+
+MDZ003-VAZ052>055-
+057-020000-
+/O.NEW.KLWX.WS.W.0003.220101T1200Z-220102T0000Z/
+/O.CON.KLWX.WW.Y.0002.000000T0000Z-220102T0000Z/
+Washington-Fauquier-
+218 PM EST Sat Jan 1 2022
+
+...WINTER STORM WARNING IN EFFECT UNTIL 7 PM EST SUNDAY...
+
+* WHAT...Heavy snow expected.
+
+$$
+
+DCZ001-020000-
+/O.EXT.KLWX.WW.Y.0002.000000T0000Z-220102T0000Z/
+District of Columbia-
+
+* WHAT...Snow.
+
+$$
+";
+    
+    #[test]
+    fn parses_heading() {
+        assert_eq!(WmoHeading::parse("WWUS41 KLWX 011918 CCA"), Some(WmoHeading {
+            designator: "WWUS41".to_owned(),
+            office: "KLWX".to_owned(),
+            day: 1,
+            hour: 19,
+            minute: 18,
+            bbb: Some("CCA".to_owned()),
+        }));
+        assert!(WmoHeading::parse("FXUS61 KLWX 011918").is_some_and(|heading| heading.bbb.is_none()));
+    }
+    
+    #[test]
+    fn rejects_malformed_heading() {
+        for line in ["", "FXUS61 KLWX", "FXUS6 KLWX 011918", "FXUS61 KLWX 0119", "FXUS61 KLWX 011918 cca", "fxus61 KLWX 011918",
+                     "FXUS61 KLWX 011918 CCA EXTRA", "FXÜS1 KLWX 011918", "FXUÜ1 KLWX 011918", "ÄÄÄ KLWX 011918", "FXUS61 KLWÜ 011918", "FXUS61 KLWX 01191Ä"] {
+            assert_eq!(WmoHeading::parse(line), None, "{:?}", line);
+        }
+    }
+    
+    #[test]
+    fn parses_unsegmented_product() {
+        let product = TextProduct::parse(AFD).unwrap();
+        
+        assert_eq!(product.heading.designator, "FXUS61");
+        assert_eq!(product.awips_id.as_deref(), Some("AFDLWX"));
+        assert_eq!(product.product_name.as_deref(), Some("Area Forecast Discussion"));
+        assert_eq!(product.issuing_office.as_deref(), Some("National Weather Service Baltimore MD/Washington DC"));
+        assert_eq!(product.issuance.as_deref(), Some("318 PM EDT Sun May 1 2022"));
+        
+        assert_eq!(product.segments.len(), 2);
+        let segment = &product.segments[0];
+        assert_eq!(segment.ugc, None);
+        assert!(segment.vtec.is_empty());
+        assert_eq!(segment.sections.len(), 2);
+        assert_eq!(segment.sections[0].title.as_deref(), Some("SYNOPSIS"));
+        assert_eq!(segment.sections[0].body, "High pressure will build overhead tonight.\nA cold front approaches Tuesday.");
+        
+        let near_term = product.section("near term /through tonight/").unwrap();
+        assert_eq!(near_term.body, "Clouds clear out this evening.\nLows in the 40s.");
+        assert_eq!(product.segments[1].sections[0].body, "SYNOPSIS...KLW");
+    }
+    
+    #[test]
+    fn splits_segments() {
+        let product = TextProduct::parse(WSW).unwrap();
+        
+        assert_eq!(product.heading.bbb.as_deref(), Some("CCA"));
+        assert_eq!(product.product_name.as_deref(), Some("URGENT - WINTER WEATHER MESSAGE"));
+        assert_eq!(product.segments.len(), 2);
+        
+        let first = &product.segments[0];
+        assert_eq!(first.ugc.as_deref(), Some("MDZ003-VAZ052>055-057-020000-"));
+        assert_eq!(first.vtec.len(), 2);
+        assert_eq!(first.parse_vtec().len(), 2);
+        assert_eq!(first.parse_ugc().unwrap().codes.len(), 6);
+        assert!(first.sections[0].body.starts_with("Washington-Fauquier-"));
+        
+        let second = &product.segments[1];
+        assert_eq!(second.ugc.as_deref(), Some("DCZ001-020000-"));
+        assert_eq!(second.vtec, vec!["/O.EXT.KLWX.WW.Y.0002.000000T0000Z-220102T0000Z/"]);
+    }
+    
+    #[test]
+    fn rejects_text_without_heading() {
+        assert!(TextProduct::parse("").is_none());
+        assert!(TextProduct::parse("000\nAFDLWX\n\nArea Forecast Discussion").is_none());
+        assert!(TextProduct::parse("Ü\nFXUS61 KLWX 011918").is_none());
+    }
+}
@@ -1,12 +1,16 @@
+use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
-use chrono::{DateTime, NaiveDateTime, Utc};
+use chrono::{DateTime, FixedOffset, NaiveDateTime, Utc};
 use select::document::Document;
 use select::node::Node;
-use select::predicate::{Child, Name};
+use select::predicate::Name;
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use serde::de::{Error, Unexpected, Visitor};
+use serde_json::Value;
 use url::Url;
-use crate::{NwsError, ReqClient};
+use wkt::Wkt;
+use crate::{NwsError, ScrapeError, ScrapeErrorKind, DATETIME_FMT};
+use super::{QuantitativeValue, serialize_wkt, deserialize_wkt};
 
 
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -102,16 +106,13 @@ pub enum FileSize {
     G(f64),
 }
 impl FileSize {
+    /// Parses a directory listing size such as `512`, `1.5K` or `20M`.
+    /// 
+    /// `None` for sizes the listing leaves out (`-` or empty, as for directories) and for anything unparsable.
     pub fn new(value: impl AsRef<str>) -> Option<Self> {
-        let value = value.as_ref();
-        if value.is_empty() || value == "-" { return None; }
-        
-        let last = value.chars().last().unwrap();
-        let num: f64 = if last.is_numeric() {
-            value.parse().unwrap()
-        } else {
-            value.split_at(value.len() - 1).0.parse().unwrap()
-        };
+        let value = value.as_ref().trim();
+        let last = value.chars().last()?;
+        let num: f64 = value.strip_suffix(['K', 'M', 'G']).unwrap_or(value).parse().ok()?;
         
         use FileSize::*;
         match last {
@@ -119,7 +120,7 @@ impl FileSize {
             'M' => Some(M(num)),
             'G' => Some(G(num)),
             
-            _ if last.is_numeric() => Some(B(num)),
+            _ if last.is_ascii_digit() => Some(B(num)),
             _ => None
         }
     }
@@ -139,26 +140,37 @@ impl RemoteFile {
     }}
 }
 
-pub(crate) fn crawl(req: &ReqClient, url: impl AsRef<str>) -> Result<Vec<RemoteFile>, NwsError> {
-    let url = url.as_ref();
-    let mut files = vec![];
-    
-    let resp = match req.get(url).send() {
-        Ok(resp) => resp,
-        Err(err) => return Err(err.into())
-    };
-    let html = resp.text().unwrap();
-    let doc = Document::from(html.as_ref());
+/// Parses an Apache-style directory listing, such as an MRMS product directory, into the files it lists.
+/// 
+/// Each `tr` with `td` cells is one entry: name (linked), last modified and size. Rows with only `th` cells are
+/// headers and are skipped. Any other row that doesn't fit that shape is reported as a [`ScrapeError`] rather than
+/// silently dropped.
+pub(crate) fn parse_listing(url: &str, html: &str) -> Result<Vec<RemoteFile>, NwsError> {
+    let base = Url::parse(url).map_err(|_| ScrapeError {
+        url: url.to_owned(),
+        row: String::new(),
+        kind: ScrapeErrorKind::InvalidUrl(url.to_owned()),
+    })?;
+    let doc = Document::from(html);
     
-    let select: Vec<Node> = doc.select(Child(Name("tr"), Name("td"))).collect();
-    for i in (0..select.len()).step_by(3) {
-        let name = select[i].text().trim().to_owned();
-        let modified = select[i + 1].text().trim().to_owned();
-        let size = select[i + 2].text().trim().to_owned();
+    let mut files = vec![];
+    for row in doc.select(Name("tr")) {
+        let cells: Vec<Node> = row.children().filter(|child| child.is(Name("td"))).collect();
+        if cells.is_empty() { continue; }
+        let fail = |kind| ScrapeError { url: url.to_owned(), row: row.html(), kind };
+        
+        let [name, modified, size] = match cells.as_slice() {
+            [name, modified, size, ..] => [name, modified, size],
+            _ => return Err(fail(ScrapeErrorKind::MissingCells { expected: 3, found: cells.len() }).into()),
+        };
+        let name = name.text().trim().to_owned();
+        let modified = modified.text().trim().to_owned();
+        let size = size.text().trim().to_owned();
         
         let url = if name == "Parent Directory" && size == "-" {
-            let url = Url::parse(url).unwrap();
-            format!("{}://{}{}", url.scheme(), url.host_str().unwrap(), select[i].first_child().unwrap().attr("href").unwrap())
+            let href = cells[0].select(Name("a")).next().and_then(|link| link.attr("href"))
+                .ok_or_else(|| fail(ScrapeErrorKind::MissingLink))?;
+            base.join(href).map_err(|_| fail(ScrapeErrorKind::InvalidUrl(href.to_owned())))?.to_string()
         } else {
             format!("{}{}", url, name)
         };
@@ -166,11 +178,437 @@ pub(crate) fn crawl(req: &ReqClient, url: impl AsRef<str>) -> Result<Vec<RemoteF
         let modified = if modified.is_empty() {
             None
         } else {
-            Some(DateTime::from_utc(NaiveDateTime::parse_from_str(&modified, "%d-%b-%Y %H:%M").unwrap(), Utc))
+            let modified = NaiveDateTime::parse_from_str(&modified, "%d-%b-%Y %H:%M")
+                .map_err(|_| fail(ScrapeErrorKind::InvalidDate(modified.clone())))?;
+            Some(DateTime::from_utc(modified, Utc))
+        };
+        
+        let size = match FileSize::new(&size) {
+            None if !(size.is_empty() || size == "-") => return Err(fail(ScrapeErrorKind::InvalidSize(size)).into()),
+            size => size,
         };
         
-        files.push(RemoteFile::new(url, modified, FileSize::new(size)));
+        files.push(RemoteFile::new(url, modified, size));
     }
     
     Ok(files)
-}
\ No newline at end of file
+}
+
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RadarLatency {
+    pub current: QuantitativeValue,
+    pub average: QuantitativeValue,
+    pub max: QuantitativeValue,
+    pub level_two_last_received_time: Option<DateTime<FixedOffset>>,
+    pub max_latency_time: Option<DateTime<FixedOffset>>,
+    pub reporting_host: Option<String>,
+    pub host: Option<String>,
+}
+
+/// Status of the Radar Data Acquisition unit.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RadarRdaProperties {
+    pub resolution_version: Option<String>,
+    pub nl2_path: Option<String>,
+    pub volume_coverage_pattern: Option<String>,
+    /// Which Radar Product Generator may control the RDA, e.g. `RPG Remote Only`.
+    pub control_status: Option<String>,
+    pub build_number: Option<f64>,
+    pub alarm_summary: Option<String>,
+    pub mode: Option<String>,
+    pub generator_state: Option<String>,
+    pub super_resolution_status: Option<String>,
+    pub operability_status: Option<String>,
+    pub status: Option<String>,
+    pub average_transmitter_power: Option<QuantitativeValue>,
+    pub reflectivity_calibration_correction: Option<QuantitativeValue>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RadarRda {
+    pub timestamp: DateTime<FixedOffset>,
+    pub reporting_host: Option<String>,
+    pub properties: RadarRdaProperties,
+}
+
+/// Status of the Radar Product Generator, which turns the RDA's data into products and distributes them.
+/// 
+/// Fields the API sends in an unexpected shape are `None`; keys not modeled here are kept in `other`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RadarRpgProperties {
+    #[serde(default, deserialize_with = "deserialize_lenient")]
+    pub status: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_lenient")]
+    pub operability_status: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_lenient")]
+    pub mode: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_lenient")]
+    pub alarm_summary: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_lenient")]
+    pub volume_coverage_pattern: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_lenient")]
+    pub build_number: Option<f64>,
+    #[serde(flatten)]
+    pub other: HashMap<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RadarRpg {
+    pub timestamp: DateTime<FixedOffset>,
+    pub reporting_host: Option<String>,
+    pub properties: RadarRpgProperties,
+}
+
+/// Hardware readings of the radar site: power, channel, temperatures and transmitter health.
+/// 
+/// Fields the API sends in an unexpected shape are `None`; keys not modeled here are kept in `other`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RadarPerformanceProperties {
+    /// The RDA channel in use, e.g. `RDA 1`.
+    #[serde(default, deserialize_with = "deserialize_lenient")]
+    pub commanded_channel: Option<String>,
+    /// E.g. `Utility` or `Generator`.
+    #[serde(default, deserialize_with = "deserialize_lenient")]
+    pub power_source: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_lenient")]
+    pub transitional_power_source: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_lenient")]
+    pub fuel_level: Option<QuantitativeValue>,
+    #[serde(default, deserialize_with = "deserialize_lenient")]
+    pub shelter_temperature: Option<QuantitativeValue>,
+    #[serde(default, deserialize_with = "deserialize_lenient")]
+    pub radome_air_temperature: Option<QuantitativeValue>,
+    #[serde(default, deserialize_with = "deserialize_lenient")]
+    pub transmitter_leaving_air_temperature: Option<QuantitativeValue>,
+    #[serde(default, deserialize_with = "deserialize_lenient")]
+    pub transmitter_peak_power: Option<QuantitativeValue>,
+    #[serde(default, deserialize_with = "deserialize_lenient")]
+    pub transmitter_recycle_count: Option<u64>,
+    #[serde(default, deserialize_with = "deserialize_lenient")]
+    pub transmitter_imbalance: Option<QuantitativeValue>,
+    #[serde(default, deserialize_with = "deserialize_lenient")]
+    pub receiver_bias: Option<QuantitativeValue>,
+    #[serde(default, deserialize_with = "deserialize_lenient")]
+    pub dynamic_range: Option<QuantitativeValue>,
+    #[serde(default, deserialize_with = "deserialize_lenient")]
+    pub horizontal_noise_temperature: Option<QuantitativeValue>,
+    #[serde(default, deserialize_with = "deserialize_lenient")]
+    pub azimuth_encoder_light: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_lenient")]
+    pub elevation_encoder_light: Option<String>,
+    #[serde(default, rename = "ntp_status", deserialize_with = "deserialize_lenient")]
+    pub ntp_status: Option<i64>,
+    #[serde(flatten)]
+    pub other: HashMap<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RadarPerformance {
+    pub timestamp: DateTime<FixedOffset>,
+    pub reporting_host: Option<String>,
+    pub properties: RadarPerformanceProperties,
+}
+
+/// Deserializes a value, or `None` if it doesn't have the expected shape, for status documents that differ between
+/// station types.
+fn deserialize_lenient<'de, D: Deserializer<'de>, T: de::DeserializeOwned>(deserializer: D) -> Result<Option<T>, D::Error> {
+    Ok(serde_json::from_value(Value::deserialize(deserializer)?).ok())
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RadarStation {
+    #[serde(deserialize_with = "deserialize_wkt", serialize_with = "serialize_wkt")]
+    pub geometry: Wkt<f64>,
+    #[serde(rename = "@id")]
+    pub id: Url,
+    #[serde(rename = "@type")]
+    pub kind: String,
+    #[serde(rename = "id")]
+    pub identifier: String,
+    pub name: String,
+    pub station_type: String,
+    pub elevation: QuantitativeValue,
+    pub time_zone: String,
+    pub latency: Option<RadarLatency>,
+    pub rda: Option<RadarRda>,
+    pub rpg: Option<RadarRpg>,
+    pub performance: Option<RadarPerformance>,
+    /// Not modeled; the contents vary between station types.
+    pub adaptation: Option<Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RadarStationCollection {
+    #[serde(rename = "@graph")]
+    pub graph: Vec<RadarStation>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RadarStationAlarm {
+    pub status: String,
+    pub active_channel: usize,
+    pub station_id: String,
+    pub message: String,
+    pub timestamp: DateTime<FixedOffset>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RadarStationAlarmCollection {
+    #[serde(rename = "@graph")]
+    pub graph: Vec<RadarStationAlarm>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RadarServerHardware {
+    pub timestamp: DateTime<FixedOffset>,
+    pub cpu_idle: Option<f64>,
+    pub cpu_iowait: Option<f64>,
+    pub memory: Option<f64>,
+    pub io_utilization: Option<f64>,
+    pub disk: Option<f64>,
+    pub load1: Option<f64>,
+    pub load5: Option<f64>,
+    pub load15: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RadarServerPing {
+    pub timestamp: DateTime<FixedOffset>,
+    /// Reachability of each ping target, keyed by target name.
+    #[serde(default)]
+    pub targets: HashMap<String, bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RadarServer {
+    #[serde(rename = "@id")]
+    pub id: Url,
+    #[serde(rename = "@type")]
+    pub kind: Option<String>,
+    #[serde(rename = "id")]
+    pub identifier: String,
+    #[serde(rename = "type")]
+    pub server_type: String,
+    pub up: bool,
+    pub hardware: Option<RadarServerHardware>,
+    pub ping: Option<RadarServerPing>,
+    /// Not modeled; the contents vary between server types.
+    pub command: Option<Value>,
+    /// Not modeled; the contents vary between server types.
+    pub network: Option<Value>,
+    pub collection_time: Option<DateTime<FixedOffset>>,
+    pub reporting_host: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RadarServerCollection {
+    #[serde(rename = "@graph")]
+    pub graph: Vec<RadarServer>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RadarQueueItem {
+    #[serde(rename = "@id")]
+    pub id: Url,
+    pub host: String,
+    pub arrival_time: DateTime<FixedOffset>,
+    pub created_time: DateTime<FixedOffset>,
+    pub resolution_version: Option<String>,
+    pub station: String,
+    #[serde(rename = "type")]
+    pub product_type: String,
+    pub feed: String,
+    pub sequence_number: Option<f64>,
+    pub size: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RadarQueueCollection {
+    #[serde(rename = "@graph")]
+    pub graph: Vec<RadarQueueItem>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RadarProfiler {
+    #[serde(rename = "@id")]
+    pub id: Url,
+    pub name: Option<String>,
+    pub timestamp: Option<DateTime<FixedOffset>>,
+    pub elevation: Option<QuantitativeValue>,
+    pub time: Option<String>,
+    /// Profile levels; not modeled.
+    #[serde(flatten)]
+    pub other: HashMap<String, Value>,
+}
+
+
+/// Query parameters accepted by the `/radar/queues/{host}` endpoint.
+/// 
+/// Empty lists and `None` values are omitted from the request.
+#[derive(Clone, Debug, Default)]
+pub struct RadarQueueFilter {
+    pub limit: Option<usize>,
+    pub arrived: Option<DateTime<Utc>>,
+    pub created: Option<DateTime<Utc>>,
+    pub published: Option<DateTime<Utc>>,
+    pub station: Option<String>,
+    pub product_type: Option<String>,
+    pub feed: Option<String>,
+    pub resolution_version: Option<usize>,
+}
+impl RadarQueueFilter {
+    pub(crate) fn params(&self) -> Vec<(&'static str, String)> {
+        let mut params = Vec::new();
+        if let Some(limit) = self.limit { params.push(("limit", limit.max(1).to_string())); }
+        if let Some(arrived) = self.arrived { params.push(("arrived", arrived.format(DATETIME_FMT).to_string())); }
+        if let Some(created) = self.created { params.push(("created", created.format(DATETIME_FMT).to_string())); }
+        if let Some(published) = self.published { params.push(("published", published.format(DATETIME_FMT).to_string())); }
+        if let Some(station) = &self.station { params.push(("station", station.clone())); }
+        if let Some(product_type) = &self.product_type { params.push(("type", product_type.clone())); }
+        if let Some(feed) = &self.feed { params.push(("feed", feed.clone())); }
+        if let Some(resolution_version) = self.resolution_version { params.push(("resolutionVersion", resolution_version.to_string())); }
+        
+        params
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    const STATION: &str = r#"{
+        "@id": "https://api.weather.gov/radar/stations/KTLX",
+        "@type": "wx:RadarStation",
+        "id": "KTLX",
+        "name": "Oklahoma City",
+        "stationType": "WSR-88D",
+        "geometry": "POINT(-97.2778 35.3331)",
+        "elevation": {"unitCode": "wmoUnit:m", "value": 369.7},
+        "timeZone": "America/Chicago",
+        "latency": {
+            "current": {"unitCode": "nwsUnit:s", "value": 0.9},
+            "average": {"unitCode": "nwsUnit:s", "value": 1.2},
+            "max": {"unitCode": "nwsUnit:s", "value": 4.1},
+            "levelTwoLastReceivedTime": "2022-05-01T18:55:12+00:00",
+            "maxLatencyTime": "2022-05-01T12:00:00+00:00",
+            "reportingHost": "rds",
+            "host": "rds"
+        },
+        "rda": {
+            "timestamp": "2022-05-01T18:54:00+00:00",
+            "reportingHost": "rds",
+            "properties": {
+                "resolutionVersion": null,
+                "nl2Path": "RDA",
+                "volumeCoveragePattern": "R215",
+                "controlStatus": "RPG Remote Only",
+                "buildNumber": 21,
+                "alarmSummary": "No Alarms",
+                "mode": "Operational",
+                "generatorState": "Switched to Utility",
+                "superResolutionStatus": "Enabled",
+                "operabilityStatus": "RDA - On-line",
+                "status": "Operate",
+                "averageTransmitterPower": {"unitCode": "wmoUnit:W", "value": 803},
+                "reflectivityCalibrationCorrection": {"unitCode": "wmoUnit:dB", "value": -0.44}
+            }
+        },
+        "rpg": {
+            "timestamp": "2022-05-01T18:54:30+00:00",
+            "reportingHost": "rds",
+            "properties": {
+                "status": "Operate",
+                "operabilityStatus": "Commanded Shutdown",
+                "mode": "Operational",
+                "alarmSummary": "Node Connectivity",
+                "volumeCoveragePattern": "R215",
+                "buildNumber": 21.1,
+                "loadShed": "Normal"
+            }
+        },
+        "performance": {
+            "timestamp": "2022-05-01T18:50:00+00:00",
+            "reportingHost": "rds",
+            "properties": {
+                "ntp_status": 1,
+                "commandedChannel": "RDA 1",
+                "powerSource": "Utility",
+                "transitionalPowerSource": "Off",
+                "fuelLevel": {"unitCode": "wmoUnit:percent", "value": 85},
+                "shelterTemperature": {"unitCode": "wmoUnit:degC", "value": 24.4},
+                "radomeAirTemperature": {"unitCode": "wmoUnit:degC", "value": 19.2},
+                "transmitterPeakPower": {"unitCode": "wmoUnit:kW", "value": 715.2},
+                "transmitterRecycleCount": 6,
+                "dynamicRange": "unexpected text",
+                "azimuthEncoderLight": "Ok",
+                "elevationEncoderLight": "Ok",
+                "horizontalShortPulseNoise": -80.1
+            }
+        },
+        "adaptation": {"timestamp": "2022-05-01T00:00:00+00:00", "properties": {}}
+    }"#;
+    
+    #[test]
+    fn parses_station_status() {
+        let station: RadarStation = serde_json::from_str(STATION).unwrap();
+        
+        let rda = station.rda.unwrap().properties;
+        assert_eq!(rda.operability_status.as_deref(), Some("RDA - On-line"));
+        assert_eq!(rda.control_status.as_deref(), Some("RPG Remote Only"));
+        
+        let rpg = station.rpg.unwrap().properties;
+        assert_eq!(rpg.operability_status.as_deref(), Some("Commanded Shutdown"));
+        assert_eq!(rpg.alarm_summary.as_deref(), Some("Node Connectivity"));
+        assert_eq!(rpg.build_number, Some(21.1));
+        assert_eq!(rpg.other["loadShed"], "Normal");
+        
+        let latency = station.latency.unwrap();
+        assert_eq!(latency.max.value, Some(4.1));
+    }
+    
+    #[test]
+    fn parses_station_performance() {
+        let station: RadarStation = serde_json::from_str(STATION).unwrap();
+        let performance = station.performance.unwrap().properties;
+        
+        assert_eq!(performance.commanded_channel.as_deref(), Some("RDA 1"));
+        assert_eq!(performance.power_source.as_deref(), Some("Utility"));
+        assert_eq!(performance.fuel_level.and_then(|fuel| fuel.value), Some(85.0));
+        assert_eq!(performance.transmitter_recycle_count, Some(6));
+        assert_eq!(performance.ntp_status, Some(1));
+        // A field in an unexpected shape is dropped instead of failing the station.
+        assert!(performance.dynamic_range.is_none());
+        assert_eq!(performance.other["horizontalShortPulseNoise"], -80.1);
+    }
+    
+    #[test]
+    fn parses_station_without_status() {
+        let mut station: Value = serde_json::from_str(STATION).unwrap();
+        for key in ["rda", "rpg", "performance", "adaptation", "latency"] {
+            station.as_object_mut().unwrap().remove(key);
+        }
+        let station: RadarStation = serde_json::from_value(station).unwrap();
+        
+        assert!(station.rpg.is_none() && station.performance.is_none());
+    }
+}
@@ -1,10 +1,14 @@
 
 
+use chrono::{DateTime, FixedOffset};
+use chrono_tz::Tz;
 use serde::{Serialize, Deserialize};
 use url::Url;
 use wkt::Wkt;
 use super::{QuantitativeValue, serialize_wkt, deserialize_wkt};
 
+pub mod taf;
+
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -17,7 +21,7 @@ pub struct Observation {
     pub kind: String,
     pub elevation: QuantitativeValue,
     pub station: Url,
-    pub timestamp: String,
+    pub timestamp: DateTime<FixedOffset>,
     pub raw_message: String,
     pub text_description: String,
     pub icon: Option<Url>, // API marks this as deprecated
@@ -76,7 +80,7 @@ pub struct ObservationStation {
     pub elevation: QuantitativeValue,
     pub station_identifier: String,
     pub name: String,
-    pub time_zone: String,
+    pub time_zone: Tz,
     pub forecast: Url,
     pub county: Url,
     pub fire_weather_zone: Url,
@@ -90,3 +94,21 @@ pub struct ObservationStationCollection {
     pub observation_stations: Vec<Url>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TafSummary {
+    #[serde(rename = "@id")]
+    pub id: Url,
+    #[serde(rename = "id")]
+    pub identifier: Option<String>,
+    pub issue_time: Option<DateTime<FixedOffset>>,
+    pub start: Option<DateTime<FixedOffset>>,
+    pub end: Option<DateTime<FixedOffset>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TafCollection {
+    #[serde(rename = "@graph")]
+    pub graph: Vec<TafSummary>,
+}
@@ -0,0 +1,476 @@
+//! Decoder for Terminal Aerodrome Forecasts in their traditional alphanumeric form, e.g.
+//! `TAF KIAD 011720Z 0118/0224 18010KT P6SM SCT050 FM012200 20012G20KT P6SM VCSH BKN050`.
+
+use chrono::{DateTime, Datelike, Duration, TimeZone, Utc};
+use serde::{Serialize, Deserialize};
+use crate::endpoints::QuantitativeValue;
+use crate::units::Unit;
+use super::{MetarPhenomenon, ObservationCloudLayer};
+
+const METERS_PER_STATUTE_MILE: f64 = 1609.344;
+const METERS_PER_FOOT: f64 = 0.3048;
+
+
+/// A day-of-month and time, as used throughout TAFs. The month and year are implied by the issuance date.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TafTime {
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+}
+impl TafTime {
+    /// Resolves the time against `reference`, usually the time the TAF was retrieved, choosing the month that puts it
+    /// closest to `reference`. An hour of `24` is treated as midnight of the following day.
+    pub fn resolve(&self, reference: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let candidates = [
+            (reference.year(), reference.month()),
+            if reference.month() == 1 { (reference.year() - 1, 12) } else { (reference.year(), reference.month() - 1) },
+            if reference.month() == 12 { (reference.year() + 1, 1) } else { (reference.year(), reference.month() + 1) },
+        ];
+        
+        candidates.iter()
+            .filter_map(|(year, month)| {
+                let date = Utc.ymd_opt(*year, *month, self.day).single()?;
+                let time = date.and_hms_opt(self.hour % 24, self.minute, 0)?;
+                Some(if self.hour == 24 { time + Duration::days(1) } else { time })
+            })
+            .min_by_key(|time| (*time - reference).num_seconds().abs())
+    }
+    
+    /// Parses `DDHHMM` or `DDHH`.
+    fn parse(value: &str) -> Option<Self> {
+        if !value.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        
+        match value.len() {
+            4 => Some(TafTime { day: value[0..2].parse().ok()?, hour: value[2..4].parse().ok()?, minute: 0 }),
+            6 => Some(TafTime { day: value[0..2].parse().ok()?, hour: value[2..4].parse().ok()?, minute: value[4..6].parse().ok()? }),
+            _ => None
+        }
+    }
+    
+    /// Parses a `DDHH/DDHH` validity period.
+    fn parse_period(value: &str) -> Option<(Self, Self)> {
+        let (start, end) = value.split_once('/')?;
+        if start.len() != 4 || end.len() != 4 {
+            return None;
+        }
+        
+        Some((TafTime::parse(start)?, TafTime::parse(end)?))
+    }
+}
+
+/// How a forecast group relates to the conditions before it.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TafChange {
+    /// The initial conditions following the TAF header.
+    Base,
+    /// `FM`: conditions change completely at the start time.
+    From,
+    /// `BECMG`: conditions change gradually over the period.
+    Becoming,
+    /// `TEMPO`: temporary fluctuations during the period.
+    Temporary,
+    /// `PROBnn`: conditions with the given percent probability during the period.
+    Probability(u8),
+    /// `PROBnn TEMPO`: temporary fluctuations with the given percent probability during the period.
+    ProbabilityTemporary(u8),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TafWind {
+    /// Direction in degrees, or `None` if variable.
+    pub direction: Option<f64>,
+    pub speed: f64,
+    pub gust: Option<f64>,
+    /// `KT` or `MPS`.
+    pub unit: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TafGroup {
+    pub change: TafChange,
+    pub start: Option<TafTime>,
+    pub end: Option<TafTime>,
+    pub wind: Option<TafWind>,
+    /// Prevailing visibility in meters. For `P6SM`-style values the value is stored as `min_value`.
+    pub visibility: Option<QuantitativeValue>,
+    pub weather: Vec<MetarPhenomenon>,
+    pub cloud_layers: Vec<ObservationCloudLayer>,
+    pub raw: String,
+}
+impl TafGroup {
+    fn new(change: TafChange, start: Option<TafTime>, end: Option<TafTime>) -> Self { Self {
+        change,
+        start,
+        end,
+        wind: None,
+        visibility: None,
+        weather: vec![],
+        cloud_layers: vec![],
+        raw: String::new(),
+    }}
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Taf {
+    pub station: String,
+    pub amended: bool,
+    pub corrected: bool,
+    pub issued: TafTime,
+    pub valid_from: TafTime,
+    pub valid_to: TafTime,
+    pub groups: Vec<TafGroup>,
+}
+impl Taf {
+    /// Decodes a single TAF. Line breaks are not significant, and a leading `TAF` keyword and trailing `=` are
+    /// optional. Returns `None` if the header is malformed; unrecognized tokens within groups are skipped.
+    pub fn decode(raw: impl AsRef<str>) -> Option<Self> {
+        let mut tokens = raw.as_ref().split_whitespace().map(|token| token.trim_end_matches('=')).peekable();
+        
+        let mut amended = false;
+        let mut corrected = false;
+        while let Some(token) = tokens.peek() {
+            match *token {
+                "TAF" => {},
+                "AMD" => amended = true,
+                "COR" => corrected = true,
+                _ => break
+            }
+            tokens.next();
+        }
+        
+        let station = tokens.next()?.to_string();
+        let issued = TafTime::parse(tokens.next()?.strip_suffix('Z')?)?;
+        let (valid_from, valid_to) = TafTime::parse_period(tokens.next()?)?;
+        
+        let mut groups = vec![TafGroup::new(TafChange::Base, Some(valid_from), Some(valid_to))];
+        let mut raw: Vec<&str> = vec![];
+        while let Some(token) = tokens.next() {
+            if token == "RMK" {
+                break;
+            }
+            
+            let change = if let Some(time) = token.strip_prefix("FM") {
+                TafTime::parse(time).map(|time| TafGroup::new(TafChange::From, Some(time), None))
+            } else if let Some(change) = parse_change(token) {
+                let change = match change {
+                    TafChange::Probability(probability) if tokens.peek() == Some(&"TEMPO") => {
+                        tokens.next();
+                        TafChange::ProbabilityTemporary(probability)
+                    },
+                    change => change,
+                };
+                let period = tokens.peek().and_then(|token| TafTime::parse_period(token));
+                if period.is_some() {
+                    tokens.next();
+                }
+                Some(TafGroup::new(change, period.map(|(start, _)| start), period.map(|(_, end)| end)))
+            } else {
+                None
+            };
+            
+            if let Some(group) = change {
+                if let Some(last) = groups.last_mut() {
+                    last.raw = raw.join(" ");
+                }
+                raw.clear();
+                groups.push(group);
+                continue;
+            }
+            
+            raw.push(token);
+            let group = groups.last_mut()?;
+            if let Some(wind) = parse_wind(token) {
+                group.wind = Some(wind);
+            } else if token == "CAVOK" {
+                group.visibility = Some(meters(None, Some(10000.0)));
+            } else if let Some(visibility) = parse_visibility(token, tokens.peek().copied()) {
+                if token.len() == 1 && tokens.peek().is_some_and(|next| next.ends_with("SM")) {
+                    raw.push(tokens.next()?);
+                }
+                group.visibility = Some(visibility);
+            } else if let Some(layer) = parse_cloud_layer(token) {
+                group.cloud_layers.push(layer);
+            } else {
+                group.weather.extend(parse_weather(token));
+            }
+        }
+        if let Some(last) = groups.last_mut() {
+            last.raw = raw.join(" ");
+        }
+        
+        Some(Taf {
+            station,
+            amended,
+            corrected,
+            issued,
+            valid_from,
+            valid_to,
+            groups,
+        })
+    }
+}
+
+
+/// Parses the `BECMG`, `TEMPO` and `PROBnn` change indicators. A malformed `PROB` group is not a change indicator.
+fn parse_change(token: &str) -> Option<TafChange> {
+    match token {
+        "BECMG" => Some(TafChange::Becoming),
+        "TEMPO" => Some(TafChange::Temporary),
+        _ => {
+            let probability = token.strip_prefix("PROB")?;
+            if probability.len() != 2 || !probability.chars().all(|c| c.is_ascii_digit()) {
+                return None;
+            }
+            Some(TafChange::Probability(probability.parse().ok()?))
+        }
+    }
+}
+
+fn meters(value: Option<f64>, min_value: Option<f64>) -> QuantitativeValue {
+    QuantitativeValue {
+        unit_code: Some(Unit::Meter),
+        value,
+        max_value: None,
+        min_value,
+        quality_control: None,
+    }
+}
+
+/// Parses `dddffKT`, `dddffGggKT`, `VRBffKT` and the `MPS` equivalents.
+fn parse_wind(token: &str) -> Option<TafWind> {
+    let (body, unit) = if let Some(body) = token.strip_suffix("KT") {
+        (body, "KT")
+    } else if let Some(body) = token.strip_suffix("MPS") {
+        (body, "MPS")
+    } else {
+        return None;
+    };
+    if body.len() < 5 || !body.is_ascii() {
+        return None;
+    }
+    
+    let direction = match &body[..3] {
+        "VRB" => None,
+        direction => Some(direction.parse().ok()?),
+    };
+    let (speed, gust) = match body[3..].split_once('G') {
+        Some((speed, gust)) => (speed.parse().ok()?, Some(gust.parse().ok()?)),
+        None => (body[3..].parse().ok()?, None),
+    };
+    
+    Some(TafWind { direction, speed, gust, unit: unit.to_owned() })
+}
+
+/// Parses statute mile visibilities (`P6SM`, `3SM`, `1/2SM`, and `1 1/2SM` across two tokens) and four-digit meter
+/// visibilities (`9999`).
+fn parse_visibility(token: &str, next: Option<&str>) -> Option<QuantitativeValue> {
+    fn fraction(value: &str) -> Option<f64> {
+        match value.split_once('/') {
+            Some((num, den)) => Some(num.parse::<f64>().ok()? / den.parse::<f64>().ok()?),
+            None => value.parse().ok(),
+        }
+    }
+    
+    if token.len() == 1 && token.chars().all(|c| c.is_ascii_digit()) {
+        let next = next?.strip_suffix("SM")?;
+        let miles = token.parse::<f64>().ok()? + fraction(next)?;
+        return Some(meters(Some(miles * METERS_PER_STATUTE_MILE), None));
+    }
+    if token.len() == 4 && token.chars().all(|c| c.is_ascii_digit()) {
+        let value: f64 = token.parse().ok()?;
+        return Some(if token == "9999" { meters(None, Some(10000.0)) } else { meters(Some(value), None) });
+    }
+    
+    let miles = token.strip_suffix("SM")?;
+    match miles.strip_prefix('P') {
+        Some(miles) => Some(meters(None, Some(fraction(miles)? * METERS_PER_STATUTE_MILE))),
+        None => Some(meters(Some(fraction(miles)? * METERS_PER_STATUTE_MILE), None)),
+    }
+}
+
+/// Parses `FEW050`, `BKN020CB`, `VV003`, `SKC`, `NSC` and `CLR`. Heights are converted to meters.
+fn parse_cloud_layer(token: &str) -> Option<ObservationCloudLayer> {
+    if matches!(token, "SKC" | "NSC" | "CLR" | "NCD") {
+        return Some(ObservationCloudLayer { base: meters(None, None), amount: token.to_owned() });
+    }
+    
+    let (amount, rest) = ["FEW", "SCT", "BKN", "OVC", "VV"].iter()
+        .find_map(|amount| token.strip_prefix(amount).map(|rest| (*amount, rest)))?;
+    let height = rest.get(..3)?;
+    let suffix = &rest[3..];
+    if !matches!(suffix, "" | "CB" | "TCU") {
+        return None;
+    }
+    let height: f64 = height.parse().ok()?;
+    
+    Some(ObservationCloudLayer {
+        base: meters(Some(height * 100.0 * METERS_PER_FOOT), None),
+        amount: amount.to_owned(),
+    })
+}
+
+/// Parses present weather groups such as `-SHRA`, `+TSRAGR`, `VCSH` or `BR` into one phenomenon per weather type.
+/// Returns an empty list if the token is not a weather group.
+fn parse_weather(token: &str) -> Vec<MetarPhenomenon> {
+    const DESCRIPTORS: &[(&str, &str)] = &[
+        ("MI", "shallow"), ("PR", "partial"), ("BC", "patches"), ("DR", "low_drifting"), ("BL", "blowing"),
+        ("SH", "showers"), ("FZ", "freezing"), ("TS", "thunderstorms"),
+    ];
+    const PHENOMENA: &[(&str, &str)] = &[
+        ("BR", "fog_mist"), ("DS", "dust_storm"), ("DU", "dust"), ("DZ", "drizzle"), ("FC", "funnel_cloud"),
+        ("FG", "fog"), ("FU", "smoke"), ("GR", "hail"), ("GS", "snow_pellets"), ("HZ", "haze"), ("IC", "ice_crystals"),
+        ("PL", "ice_pellets"), ("PO", "dust_whirls"), ("PY", "spray"), ("RA", "rain"), ("SA", "sand"),
+        ("SG", "snow_grains"), ("SN", "snow"), ("SQ", "squalls"), ("SS", "sand_storm"), ("UP", "unknown"),
+        ("VA", "volcanic_ash"),
+    ];
+    
+    let (intensity, rest) = if let Some(rest) = token.strip_prefix('-') {
+        (Some("light"), rest)
+    } else if let Some(rest) = token.strip_prefix('+') {
+        (Some("heavy"), rest)
+    } else {
+        (None, token)
+    };
+    let (in_vicinity, rest) = match rest.strip_prefix("VC") {
+        Some(rest) => (true, rest),
+        None => (false, rest),
+    };
+    if rest.is_empty() || rest.len() % 2 != 0 || !rest.is_ascii() {
+        return vec![];
+    }
+    
+    let codes: Vec<&str> = (0..rest.len()).step_by(2).map(|i| &rest[i..i + 2]).collect();
+    let descriptor = DESCRIPTORS.iter().find(|(code, _)| *code == codes[0]).map(|(_, name)| *name);
+    let phenomena = if descriptor.is_some() { &codes[1..] } else { &codes[..] };
+    let mut names = vec![];
+    for code in phenomena {
+        match PHENOMENA.iter().find(|(phenomenon, _)| phenomenon == code) {
+            Some((_, name)) => names.push(*name),
+            None => return vec![],
+        }
+    }
+    
+    let new = |weather: &str, modifier: Option<&str>| MetarPhenomenon {
+        intensity: intensity.map(|intensity| intensity.to_owned()),
+        modifier: modifier.map(|modifier| modifier.to_owned()),
+        weather: weather.to_owned(),
+        raw_string: token.to_owned(),
+        in_vicinity: Some(in_vicinity),
+    };
+    match descriptor {
+        Some("thunderstorms") => std::iter::once(new("thunderstorms", None)).chain(names.iter().map(|name| new(name, None))).collect(),
+        Some(descriptor) if names.is_empty() => vec![new(descriptor, None)],
+        descriptor => names.iter().map(|name| new(name, descriptor)).collect(),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    const KIAD: &str = "TAF KIAD 011720Z 0118/0224 18010KT P6SM SCT050
+      FM012200 20012G20KT P6SM VCSH BKN050
+      TEMPO 0200/0204 3SM -SHRA BKN030
+      PROB30 0206/0210 1 1/2SM TSRA BKN020CB
+      BECMG 0212/0214 VRB03KT 9999 SKC=";
+    
+    fn time(day: u32, hour: u32, minute: u32) -> TafTime {
+        TafTime { day, hour, minute }
+    }
+    
+    #[test]
+    fn decodes_header() {
+        let taf = Taf::decode(KIAD).unwrap();
+        
+        assert_eq!(taf.station, "KIAD");
+        assert!(!taf.amended && !taf.corrected);
+        assert_eq!(taf.issued, time(1, 17, 20));
+        assert_eq!((taf.valid_from, taf.valid_to), (time(1, 18, 0), time(2, 24, 0)));
+    }
+    
+    #[test]
+    fn decodes_change_groups() {
+        let taf = Taf::decode(KIAD).unwrap();
+        let changes: Vec<TafChange> = taf.groups.iter().map(|group| group.change).collect();
+        
+        assert_eq!(changes, [TafChange::Base, TafChange::From, TafChange::Temporary, TafChange::Probability(30), TafChange::Becoming]);
+        assert_eq!(taf.groups[0].raw, "18010KT P6SM SCT050");
+        assert_eq!(taf.groups[1].start, Some(time(1, 22, 0)));
+        assert_eq!(taf.groups[1].end, None);
+        assert_eq!((taf.groups[2].start, taf.groups[2].end), (Some(time(2, 0, 0)), Some(time(2, 4, 0))));
+        
+        let prob = &taf.groups[3];
+        assert_eq!(prob.raw, "1 1/2SM TSRA BKN020CB");
+        let visibility = prob.visibility.as_ref().unwrap().value.unwrap();
+        assert!((visibility - 1.5 * METERS_PER_STATUTE_MILE).abs() < 1e-6);
+        let weather: Vec<&str> = prob.weather.iter().map(|weather| weather.weather.as_str()).collect();
+        assert_eq!(weather, ["thunderstorms", "rain"]);
+        assert_eq!(prob.cloud_layers[0].amount, "BKN");
+        
+        let becoming = &taf.groups[4];
+        assert_eq!(becoming.visibility.as_ref().unwrap().min_value, Some(10000.0));
+        assert_eq!(becoming.cloud_layers[0].amount, "SKC");
+    }
+    
+    #[test]
+    fn decodes_probability_tempo_and_amendments() {
+        let taf = Taf::decode("TAF AMD KOKC 012335Z 0200/0224 VRB05KT P6SM FEW250 PROB40 TEMPO 0203/0206 30015G25KT 2SM +TSRA OVC010CB").unwrap();
+        
+        assert!(taf.amended);
+        assert_eq!(taf.groups[1].change, TafChange::ProbabilityTemporary(40));
+        assert_eq!(taf.groups[1].raw, "30015G25KT 2SM +TSRA OVC010CB");
+        assert_eq!(taf.groups[1].weather[1].intensity.as_deref(), Some("heavy"));
+    }
+    
+    #[test]
+    fn decodes_winds() {
+        assert_eq!(parse_wind("20012G20KT"), Some(TafWind { direction: Some(200.0), speed: 12.0, gust: Some(20.0), unit: "KT".to_owned() }));
+        assert_eq!(parse_wind("VRB03KT"), Some(TafWind { direction: None, speed: 3.0, gust: None, unit: "KT".to_owned() }));
+        assert_eq!(parse_wind("VRB10G25KT").unwrap().gust, Some(25.0));
+        assert_eq!(parse_wind("24008MPS").unwrap().unit, "MPS");
+        for token in ["KT", "200KT", "20A12KT", "P6SM", "18Ä10KT", "ÄÄÄÄÄKT", "VRBÄ1KT"] {
+            assert_eq!(parse_wind(token), None, "{:?}", token);
+        }
+    }
+    
+    #[test]
+    fn skips_malformed_groups() {
+        let taf = Taf::decode("TAF KXYZ 011720Z 0118/0224 18010KT P6SM PROBXX 0200/0204 PROB300 FM01ZZ00 SCT010").unwrap();
+        
+        assert_eq!(taf.groups.len(), 1);
+        assert_eq!(taf.groups[0].raw, "18010KT P6SM PROBXX 0200/0204 PROB300 FM01ZZ00 SCT010");
+        assert_eq!(taf.groups[0].cloud_layers.len(), 1);
+    }
+    
+    #[test]
+    fn survives_non_ascii_input() {
+        let taf = Taf::decode("TAF KIAD 011720Z 0118/0224 18Ä10KT P6SM -SHRÄ Ä SCTÄ50 TEMPO 0200/0204 ÄÄ").unwrap();
+        
+        assert!(taf.groups[0].wind.is_none());
+        assert!(taf.groups[0].weather.is_empty());
+        assert!(taf.groups[0].cloud_layers.is_empty());
+        assert_eq!(taf.groups[1].change, TafChange::Temporary);
+        
+        assert!(Taf::decode("TAF KIAD 01172ÄZ 0118/0224").is_none());
+        assert!(Taf::decode("TAF KIAD 011720Z 01Ä8/0224").is_none());
+    }
+    
+    #[test]
+    fn rejects_malformed_header() {
+        for raw in ["", "TAF", "TAF KIAD", "TAF KIAD 011720 0118/0224", "TAF KIAD 011720Z 0118-0224", "TAF KIAD 011720Z 01180224"] {
+            assert!(Taf::decode(raw).is_none(), "{:?}", raw);
+        }
+    }
+    
+    #[test]
+    fn resolves_times_across_month_boundaries() {
+        let reference = Utc.ymd(2022, 5, 31).and_hms(23, 0, 0);
+        
+        assert_eq!(time(1, 6, 0).resolve(reference), Some(Utc.ymd(2022, 6, 1).and_hms(6, 0, 0)));
+        assert_eq!(time(31, 24, 0).resolve(reference), Some(Utc.ymd(2022, 6, 1).and_hms(0, 0, 0)));
+    }
+}
@@ -0,0 +1,195 @@
+use std::fmt::{Display, Formatter};
+use chrono::{DateTime, FixedOffset, Utc};
+use geo_types::MultiPolygon;
+use serde::{Serialize, Deserialize};
+use url::Url;
+use crate::DATETIME_FMT;
+use super::{serialize_multipolygon_opt, deserialize_multipolygon_opt};
+
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum ZoneType {
+    Land,
+    Marine,
+    Forecast,
+    Public,
+    Coastal,
+    Offshore,
+    Fire,
+    County,
+}
+impl Display for ZoneType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        use ZoneType::*;
+        write!(f, "{}", match self {
+            Land => "land",
+            Marine => "marine",
+            Forecast => "forecast",
+            Public => "public",
+            Coastal => "coastal",
+            Offshore => "offshore",
+            Fire => "fire",
+            County => "county",
+        })
+    }
+}
+impl ZoneType {
+    pub fn values() -> Vec<ZoneType> {
+        use ZoneType::*;
+        vec![Land, Marine, Forecast, Public, Coastal, Offshore, Fire, County]
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Zone {
+    #[serde(default, deserialize_with = "deserialize_multipolygon_opt", serialize_with = "serialize_multipolygon_opt")]
+    pub geometry: Option<MultiPolygon<f64>>,
+    #[serde(rename = "@id")]
+    pub id: Url,
+    #[serde(rename = "@type")]
+    pub kind: String,
+    #[serde(rename = "id")]
+    pub identifier: String,
+    #[serde(rename = "type")]
+    pub zone_type: ZoneType,
+    pub name: String,
+    pub effective_date: Option<DateTime<FixedOffset>>,
+    pub expiration_date: Option<DateTime<FixedOffset>>,
+    pub state: Option<String>,
+    #[serde(default)]
+    pub cwa: Vec<String>,
+    #[serde(default)]
+    pub forecast_offices: Vec<Url>,
+    #[serde(default)]
+    pub time_zone: Vec<String>,
+    #[serde(default)]
+    pub observation_stations: Vec<Url>,
+    pub radar_station: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ZoneCollection {
+    #[serde(rename = "@graph")]
+    pub graph: Vec<Zone>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ZoneForecastPeriod {
+    pub number: usize,
+    pub name: String,
+    pub detailed_forecast: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ZoneForecast {
+    pub zone: Url,
+    pub updated: DateTime<FixedOffset>,
+    pub periods: Vec<ZoneForecastPeriod>,
+}
+
+
+/// Query parameters accepted by the `/zones` endpoint.
+/// 
+/// Empty lists and `None` values are omitted from the request.
+#[derive(Clone, Debug, Default)]
+pub struct ZoneFilter {
+    pub id: Vec<String>,
+    /// State/territory codes, or marine area codes.
+    pub area: Vec<String>,
+    pub region: Vec<String>,
+    pub zone_type: Vec<ZoneType>,
+    /// Latitude and longitude.
+    pub point: Option<(f64, f64)>,
+    pub include_geometry: Option<bool>,
+    pub limit: Option<usize>,
+    pub effective: Option<DateTime<Utc>>,
+}
+impl ZoneFilter {
+    pub(crate) fn params(&self) -> Vec<(&'static str, String)> {
+        fn join<T: ToString>(values: &[T]) -> String {
+            values.iter().map(|v| v.to_string()).collect::<Vec<String>>().join(",")
+        }
+        
+        let mut params = Vec::new();
+        if !self.id.is_empty() { params.push(("id", join(&self.id))); }
+        if !self.area.is_empty() { params.push(("area", join(&self.area))); }
+        if !self.region.is_empty() { params.push(("region", join(&self.region))); }
+        if !self.zone_type.is_empty() { params.push(("type", join(&self.zone_type))); }
+        if let Some((lat, lon)) = self.point { params.push(("point", format!("{:.4},{:.4}", lat, lon))); }
+        if let Some(include_geometry) = self.include_geometry { params.push(("include_geometry", include_geometry.to_string())); }
+        if let Some(limit) = self.limit { params.push(("limit", limit.max(1).to_string())); }
+        if let Some(effective) = self.effective { params.push(("effective", effective.format(DATETIME_FMT).to_string())); }
+        
+        params
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn zones(geometries: &[&str]) -> ZoneCollection {
+        let zones: Vec<String> = geometries.iter().enumerate().map(|(i, geometry)| format!(r#"{{
+            "geometry": {},
+            "@id": "https://api.weather.gov/zones/forecast/KSZ{:03}",
+            "@type": "wx:Zone",
+            "id": "KSZ{:03}",
+            "type": "public",
+            "name": "Zone {}",
+            "effectiveDate": "2022-04-12T18:00:00+00:00",
+            "expirationDate": "2200-01-01T00:00:00+00:00",
+            "state": "KS",
+            "cwa": ["ICT"],
+            "forecastOffices": ["https://api.weather.gov/offices/ICT"],
+            "timeZone": ["America/Chicago"],
+            "observationStations": [],
+            "radarStation": null
+        }}"#, geometry, i, i, i)).collect();
+        
+        serde_json::from_str(&format!(r#"{{"@graph": [{}]}}"#, zones.join(","))).unwrap()
+    }
+    
+    #[test]
+    fn parses_polygon_zones() {
+        let collection = zones(&[
+            r#""POLYGON((-97 35,-96 35,-96 36,-97 35))""#,
+            r#""MULTIPOLYGON(((-97 35,-96 35,-96 36,-97 35)),((-90 30,-89 30,-89 31,-90 30)))""#,
+            "null",
+        ]);
+        
+        assert_eq!(collection.graph[0].geometry.as_ref().map(|geom| geom.0.len()), Some(1));
+        assert_eq!(collection.graph[1].geometry.as_ref().map(|geom| geom.0.len()), Some(2));
+        assert!(collection.graph[2].geometry.is_none());
+        
+        let round_trip: ZoneCollection = serde_json::from_value(serde_json::to_value(&collection).unwrap()).unwrap();
+        assert_eq!(round_trip.graph[1].geometry, collection.graph[1].geometry);
+    }
+    
+    #[test]
+    fn gathers_polygons_of_geometry_collections() {
+        let collection = zones(&[r#""GEOMETRYCOLLECTION(POLYGON((-97 35,-96 35,-96 36,-97 35)),POINT(-95 35))""#]);
+        
+        assert_eq!(collection.graph[0].geometry.as_ref().map(|geom| geom.0.len()), Some(1));
+    }
+    
+    #[test]
+    fn odd_geometries_do_not_fail_the_collection() {
+        let collection = zones(&[
+            r#""POINT(-95 35)""#,
+            r#""GEOMETRYCOLLECTION EMPTY""#,
+            r#""POLYGON EMPTY""#,
+            r#""not wkt""#,
+            r#""POLYGON((-97 35,-96 35,-96 36,-97 35))""#,
+        ]);
+        
+        assert_eq!(collection.graph.len(), 5);
+        assert!(collection.graph[..4].iter().all(|zone| zone.geometry.is_none()), "{:?}", collection.graph);
+        assert!(collection.graph[4].geometry.is_some());
+    }
+}
@@ -0,0 +1,204 @@
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+use serde::{Serialize, Deserialize};
+
+
+/// One rejected query parameter, as listed in a `400` problem document.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ParameterError {
+    pub parameter: String,
+    pub message: String,
+}
+
+/// RFC 7807 problem document returned by the API alongside error statuses.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProblemDetail {
+    /// URI identifying the kind of problem, e.g. `https://api.weather.gov/problems/NotFound`.
+    #[serde(rename = "type", default = "about_blank")]
+    pub kind: String,
+    #[serde(default)]
+    pub title: String,
+    /// HTTP status. Taken from the response itself if the document leaves it out.
+    #[serde(default)]
+    pub status: u16,
+    pub detail: Option<String>,
+    /// URN identifying this occurrence, e.g. `urn:noaa:nws:api:request:493c3a1d-f87e-407f-ae2c-24483f5aab63`.
+    pub instance: Option<String>,
+    /// Identifier to quote when reporting the problem to the NWS.
+    pub correlation_id: Option<String>,
+    #[serde(default)]
+    pub parameter_errors: Vec<ParameterError>,
+}
+impl Display for ProblemDetail {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.status, self.title)?;
+        if let Some(detail) = &self.detail {
+            write!(f, ": {}", detail)?;
+        }
+        for error in &self.parameter_errors {
+            write!(f, " ({}: {})", error.parameter, error.message)?;
+        }
+        if let Some(correlation_id) = &self.correlation_id {
+            write!(f, " [correlation id {}]", correlation_id)?;
+        }
+        
+        Ok(())
+    }
+}
+
+fn about_blank() -> String {
+    "about:blank".to_owned()
+}
+
+
+/// What was wrong with a scraped row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScrapeErrorKind {
+    /// The row had fewer cells than the listing format requires.
+    MissingCells { expected: usize, found: usize },
+    /// The row should link somewhere but has no `href`.
+    MissingLink,
+    /// The link could not be resolved against the page URL.
+    InvalidUrl(String),
+    /// The last-modified cell is not a `dd-Mon-yyyy HH:MM` timestamp.
+    InvalidDate(String),
+    /// The size cell is neither `-` nor a number with an optional `K`/`M`/`G` suffix.
+    InvalidSize(String),
+}
+impl Display for ScrapeErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        use ScrapeErrorKind::*;
+        match self {
+            MissingCells { expected, found } => write!(f, "expected {} cells, found {}", expected, found),
+            MissingLink => write!(f, "missing link"),
+            InvalidUrl(href) => write!(f, "invalid link {:?}", href),
+            InvalidDate(date) => write!(f, "invalid date {:?}", date),
+            InvalidSize(size) => write!(f, "invalid size {:?}", size),
+        }
+    }
+}
+
+/// A row of a scraped page that could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScrapeError {
+    /// Page the row came from.
+    pub url: String,
+    /// HTML of the offending row.
+    pub row: String,
+    pub kind: ScrapeErrorKind,
+}
+impl Display for ScrapeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "could not parse row of {} ({}): {}", self.url, self.kind, self.row.trim())
+    }
+}
+impl std::error::Error for ScrapeError {}
+
+
+/// Broad category of an [`NwsError`], e.g. to choose which errors a [`RetryPolicy`](crate::retry::RetryPolicy) retries.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// The request could not be sent or the response not received, e.g. a connection reset or timeout.
+    Transport,
+    /// The response body could not be deserialized.
+    Decode,
+    Api,
+    InvalidParameters,
+    RateLimited,
+    Http,
+    Scrape,
+}
+
+
+#[derive(Debug)]
+pub enum NwsError {
+    Reqwest(reqwest::Error),
+    Serde(serde_json::Error),
+    /// The API answered with an error status and a problem document.
+    Api(Box<ProblemDetail>),
+    /// The API rejected one or more query parameters (`400`).
+    InvalidParameters(Box<ProblemDetail>),
+    /// Too many requests (`429`). `retry_after` is taken from the `Retry-After` header, if sent.
+    RateLimited {
+        retry_after: Option<Duration>,
+        problem: Option<Box<ProblemDetail>>,
+    },
+    /// An error status without a readable problem document, e.g. an HTML page from a proxy.
+    Http {
+        status: u16,
+        body: String,
+    },
+    /// A scraped HTML page, such as an MRMS directory listing, was not in the expected format.
+    Scrape(Box<ScrapeError>),
+}
+impl Display for NwsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        use NwsError::*;
+        match self {
+            Reqwest(err) => write!(f, "request failed: {}", err),
+            Serde(err) => write!(f, "unexpected response: {}", err),
+            Api(problem) => write!(f, "API error: {}", problem),
+            InvalidParameters(problem) => write!(f, "invalid parameters: {}", problem),
+            RateLimited { retry_after, .. } => match retry_after {
+                Some(retry_after) => write!(f, "rate limited, retry after {}s", retry_after.as_secs()),
+                None => write!(f, "rate limited"),
+            },
+            Http { status, body } => write!(f, "HTTP {}: {}", status, body.chars().take(200).collect::<String>()),
+            Scrape(err) => write!(f, "{}", err),
+        }
+    }
+}
+impl NwsError {
+    pub fn kind(&self) -> ErrorKind {
+        use NwsError::*;
+        match self {
+            Reqwest(_) => ErrorKind::Transport,
+            Serde(_) => ErrorKind::Decode,
+            Api(_) => ErrorKind::Api,
+            InvalidParameters(_) => ErrorKind::InvalidParameters,
+            RateLimited { .. } => ErrorKind::RateLimited,
+            Http { .. } => ErrorKind::Http,
+            Scrape(_) => ErrorKind::Scrape,
+        }
+    }
+    
+    /// HTTP status of the response behind the error, if there was one.
+    pub fn status(&self) -> Option<u16> {
+        use NwsError::*;
+        match self {
+            Reqwest(err) => err.status().map(|status| status.as_u16()),
+            Api(problem) | InvalidParameters(problem) => Some(problem.status),
+            RateLimited { .. } => Some(429),
+            Http { status, .. } => Some(*status),
+            Serde(_) | Scrape(_) => None,
+        }
+    }
+}
+impl std::error::Error for NwsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            NwsError::Reqwest(err) => Some(err),
+            NwsError::Serde(err) => Some(err),
+            NwsError::Scrape(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+impl From<ScrapeError> for NwsError {
+    fn from(err: ScrapeError) -> Self {
+        NwsError::Scrape(Box::new(err))
+    }
+}
+impl From<reqwest::Error> for NwsError {
+    fn from(err: reqwest::Error) -> Self {
+        NwsError::Reqwest(err)
+    }
+}
+impl From<serde_json::Error> for NwsError {
+    fn from(err: serde_json::Error) -> Self {
+        NwsError::Serde(err)
+    }
+}
+
+pub type Result<T> = std::result::Result<T, NwsError>;
@@ -1,134 +1,481 @@
-use std::cmp::{max, min};
-use chrono::{DateTime, Utc};
-use reqwest::blocking::{Client as ReqClient, ClientBuilder, Response};
-use reqwest::Error;
+// The crate's own idioms predate these lints; keep them rather than churning the baseline code.
+#![allow(clippy::redundant_static_lifetimes, clippy::needless_return, clippy::question_mark, clippy::manual_clamp)]
+
+use std::collections::HashMap;
+use std::future::ready;
+use std::sync::Arc;
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use reqwest::blocking::{Client as ReqClient, ClientBuilder};
 use serde::de::DeserializeOwned;
-use crate::endpoints::gridpoints::{Gridpoint, GridpointStations};
-use crate::endpoints::points::Point;
-use crate::endpoints::radar::{crawl, RadarType, RemoteFile};
-use crate::endpoints::stations::{Observation, ObservationCollection, ObservationStation, ObservationStationCollection};
+use url::Url;
+use crate::endpoints::alerts::{Alert, AlertCollection, AlertCount, AlertFilter, AlertIter, AlertTypes};
+use crate::endpoints::aviation::{Cwa, CwaCollection, Cwsu, Sigmet, SigmetCollection};
+use crate::endpoints::glossary::Glossary;
+use crate::endpoints::gridpoints::{Forecast, ForecastUnits, Gridpoint, GridpointStations};
+use crate::endpoints::icons::{IconCollection, IconCondition, IconSet, IconSize, TimeOfDay};
+use crate::endpoints::offices::{Office, OfficeHeadline, OfficeHeadlineCollection};
+use crate::endpoints::points::{Point, PointCache};
+use crate::endpoints::products::{Product, ProductCollection, ProductFilter, ProductLocations, ProductTypeCollection};
+use crate::endpoints::radar::{parse_listing, RadarProfiler, RadarQueueCollection, RadarQueueFilter, RadarServer, RadarServerCollection, RadarStation, RadarStationAlarmCollection, RadarStationCollection, RadarType, RemoteFile};
+use crate::endpoints::stations::{Observation, ObservationCollection, ObservationStation, ObservationStationCollection, TafCollection};
+use crate::endpoints::zones::{Zone, ZoneCollection, ZoneFilter, ZoneForecast, ZoneType};
+use crate::request::{block_on, ApiRequest, ApiResponse, Pipeline};
+use crate::cache::{CacheStats, CacheStatus, CacheStore, Cached, ResponseCache};
+use crate::rate_limit::{RateLimit, RateLimiter};
+use crate::retry::RetryPolicy;
+use crate::transport::{ReqwestTransport, Transport};
 
+pub mod cache;
 pub mod endpoints;
+mod error;
+pub mod rate_limit;
+mod request;
+pub mod retry;
+pub mod transport;
+pub mod units;
+pub mod vtec;
+
+pub use error::{ErrorKind, NwsError, ParameterError, ProblemDetail, Result, ScrapeError, ScrapeErrorKind};
+
+#[cfg(feature = "async")]
+mod async_client;
+#[cfg(feature = "async")]
+pub use async_client::NwsAsyncClient;
 
 
 pub const ROOT: &'static str = "https://api.weather.gov";
+/// Host of the MRMS data directories crawled by `radar`.
+pub const MRMS_ROOT: &'static str = "https://mrms.ncep.noaa.gov";
 pub const DATETIME_FMT: &'static str = "%Y-%m-%dT%H:%M:%S%.3f%:z";
 
-#[derive(Debug)]
-pub enum NwsError {
-    Reqwest(reqwest::Error),
-    Serde(serde_json::Error),
-}
-impl From<reqwest::Error> for NwsError {
-    fn from(err: Error) -> Self {
-        NwsError::Reqwest(err)
-    }
-}
-impl From<serde_json::Error> for NwsError {
-    fn from(err: serde_json::Error) -> Self {
-        NwsError::Serde(err)
-    }
-}
-
-pub type Result<T> = std::result::Result<T, NwsError>;
 
 /// Client used to access the NWS API endpoints.
 /// 
-/// All functions are blocking.
-#[derive(Debug)]
+/// All functions are blocking. See `NwsAsyncClient` (behind the `async` feature) for a non-blocking client with the
+/// same methods.
+#[derive(Clone, Debug)]
 pub struct NwsClient {
+    /// The `reqwest` client behind the default transport. Requests sent through it directly bypass the base URL,
+    /// retries, rate limits and cache, and it is not used at all after [`with_transport`](Self::with_transport).
+    #[deprecated(note = "send requests through the client's methods, or plug in a `Transport` with `with_transport`")]
     pub req: ReqClient,
+    transport: Arc<dyn Transport>,
+    pipeline: Pipeline,
+    point_cache: Arc<PointCache>,
 }
 impl NwsClient {
     pub fn new(user_agent: &str) -> Result<NwsClient> {
-        match ClientBuilder::new().user_agent(user_agent).build() {
-            Ok(req) => Ok(NwsClient {
-                req
-            }),
-            Err(err) => Err(err.into())
-        }
+        let req = ClientBuilder::new().user_agent(user_agent).build()?;
+        
+        #[allow(deprecated)]
+        Ok(NwsClient {
+            transport: Arc::new(ReqwestTransport::from(req.clone())),
+            req,
+            pipeline: Pipeline::default(),
+            point_cache: Arc::default(),
+        })
+    }
+    
+    /// Sends every request through `transport` instead of `reqwest`.
+    pub fn with_transport(mut self, transport: impl Transport + 'static) -> Self {
+        self.transport = Arc::new(transport);
+        self
+    }
+    
+    /// Sends API requests to `base_url` instead of [`ROOT`], e.g. `http://localhost:8080` for a local stub server.
+    /// 
+    /// URLs under [`ROOT`] returned by the API, such as pagination links or the URLs in [`Point`], are redirected too.
+    pub fn with_base_url(mut self, base_url: impl AsRef<str>) -> Self {
+        self.pipeline.base_url = base_url.as_ref().trim_end_matches('/').to_owned();
+        self
+    }
+    
+    /// Crawls MRMS radar directories on `mrms_url` instead of [`MRMS_ROOT`].
+    pub fn with_mrms_url(mut self, mrms_url: impl AsRef<str>) -> Self {
+        self.pipeline.mrms_url = mrms_url.as_ref().trim_end_matches('/').to_owned();
+        self
+    }
+    
+    /// Replaces the default [`RetryPolicy`], which applies to every request including the radar crawl. Use
+    /// [`RetryPolicy::none`] to make a single attempt.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.pipeline.retry_policy = retry_policy;
+        self
+    }
+    
+    /// Limits API requests, including retries, to `limit`. The budget is shared by all clones of this client.
+    pub fn with_rate_limit(mut self, limit: RateLimit) -> Self {
+        self.pipeline.rate_limiter = Some(Arc::new(RateLimiter::new(limit)));
+        self
+    }
+    
+    /// Limits requests to the MRMS host crawled by `radar`, separately from the API budget.
+    pub fn with_mrms_rate_limit(mut self, limit: RateLimit) -> Self {
+        self.pipeline.mrms_rate_limiter = Some(Arc::new(RateLimiter::new(limit)));
+        self
+    }
+    
+    /// Caches responses in `store`, e.g. a [`MemoryCache`](cache::MemoryCache) or [`DiskCache`](cache::DiskCache),
+    /// following the server's freshness headers. The cache is shared by all clones of this client.
+    pub fn with_cache(mut self, store: impl CacheStore + 'static) -> Self {
+        self.pipeline.cache = Some(Arc::new(ResponseCache::new(store)));
+        self
+    }
+    
+    /// Keeps the `capacity` most recently used `/points` lookups made by the `*_at` helpers, instead of 1024. The
+    /// lookups are shared by all clones of this client made afterwards.
+    pub fn with_point_cache_capacity(mut self, capacity: usize) -> Self {
+        self.point_cache = Arc::new(PointCache::new(capacity));
+        self
+    }
+    
+    /// Cache outcomes so far. All zero if no cache is configured.
+    pub fn cache_stats(&self) -> CacheStats {
+        self.pipeline.cache.as_ref().map(|cache| cache.stats()).unwrap_or_default()
+    }
+    
+    /// Where the response to the last successful request made through this handle came from, e.g. to tell whether
+    /// [`gridpoints`](Self::gridpoints) returned cached data. `None` before the first request and after a failed one.
+    /// 
+    /// Clones keep their own status. When one handle runs requests concurrently, this is the one that finished last.
+    pub fn last_cache_status(&self) -> Option<CacheStatus> {
+        self.pipeline.last_cache_status.get()
+    }
+    
+    /// Follows any API URL, such as [`Point::forecast_grid_data`], reporting whether the response came from the cache.
+    pub fn fetch_url_cached<T: DeserializeOwned>(&self, url: &Url) -> Result<Cached<T>> {
+        self.execute_cached(&ApiRequest::url(url), ApiResponse::json)
+    }
+    
+    pub fn alerts(&self, start: Option<DateTime<Utc>>, end: Option<DateTime<Utc>>, filter: &AlertFilter, cursor: Option<&str>) -> Result<AlertCollection> {
+        self.fetch(&request::alerts(start, end, filter, cursor))
+    }
+    
+    /// Iterates over every alert matching the query, requesting further pages from `/alerts` as needed.
+    pub fn alerts_iter(&self, start: Option<DateTime<Utc>>, end: Option<DateTime<Utc>>, filter: &AlertFilter) -> AlertIter<'_> {
+        AlertIter::new(self, request::alerts(start, end, filter, None))
+    }
+    
+    /// Every alert matching the query, from all pages of `/alerts`.
+    pub fn alerts_all(&self, start: Option<DateTime<Utc>>, end: Option<DateTime<Utc>>, filter: &AlertFilter) -> Result<Vec<Alert>> {
+        self.alerts_iter(start, end, filter).collect()
+    }
+    
+    pub fn alerts_active(&self, filter: &AlertFilter) -> Result<AlertCollection> {
+        self.fetch(&request::alerts_active(filter))
+    }
+    
+    pub fn alerts_active_count(&self) -> Result<AlertCount> {
+        self.fetch(&request::alerts_active_count())
+    }
+    
+    pub fn alerts_active_zone(&self, zone_id: &str) -> Result<AlertCollection> {
+        self.fetch(&request::alerts_active_zone(zone_id))
+    }
+    
+    pub fn alerts_active_area(&self, area: &str) -> Result<AlertCollection> {
+        self.fetch(&request::alerts_active_area(area))
+    }
+    
+    pub fn alerts_active_region(&self, region: &str) -> Result<AlertCollection> {
+        self.fetch(&request::alerts_active_region(region))
+    }
+    
+    pub fn alerts_types(&self) -> Result<AlertTypes> {
+        self.fetch(&request::alerts_types())
+    }
+    
+    pub fn alerts_id(&self, id: &str) -> Result<Alert> {
+        self.fetch(&request::alerts_id(id))
+    }
+    
+    pub fn aviation_cwsus(&self, id: &str) -> Result<Cwsu> {
+        self.fetch(&request::aviation_cwsus(id))
+    }
+    
+    pub fn aviation_cwsus_cwas(&self, id: &str) -> Result<CwaCollection> {
+        self.fetch(&request::aviation_cwsus_cwas(id))
+    }
+    
+    pub fn aviation_cwsus_cwas_id(&self, id: &str, date: NaiveDate, sequence: usize) -> Result<Cwa> {
+        self.fetch(&request::aviation_cwsus_cwas_id(id, date, sequence))
+    }
+    
+    pub fn aviation_sigmets(&self, start: Option<DateTime<Utc>>, end: Option<DateTime<Utc>>, date: Option<NaiveDate>, atsu: Option<&str>, sequence: Option<&str>) -> Result<SigmetCollection> {
+        self.fetch(&request::aviation_sigmets(start, end, date, atsu, sequence))
+    }
+    
+    pub fn aviation_sigmets_atsu(&self, atsu: &str) -> Result<SigmetCollection> {
+        self.fetch(&request::aviation_sigmets_atsu(atsu))
+    }
+    
+    pub fn aviation_sigmets_atsu_date(&self, atsu: &str, date: NaiveDate) -> Result<SigmetCollection> {
+        self.fetch(&request::aviation_sigmets_atsu_date(atsu, date))
+    }
+    
+    pub fn aviation_sigmets_atsu_date_time(&self, atsu: &str, date: NaiveDate, time: NaiveTime) -> Result<Sigmet> {
+        self.fetch(&request::aviation_sigmets_atsu_date_time(atsu, date, time))
+    }
+    
+    /// Glossary terms mapped to their definitions.
+    pub fn glossary(&self) -> Result<HashMap<String, String>> {
+        let glossary: Glossary = self.fetch(&request::glossary())?;
+        
+        Ok(glossary.into_map())
     }
     
     pub fn gridpoints(&self, id: &str, x: usize, y: usize) -> Result<Gridpoint> {
-        parse_result(get(&self.req, &format!("/gridpoints/{}/{},{}", id, x, y), None))
+        self.fetch(&request::gridpoints(id, x, y))
+    }
+    
+    pub fn gridpoints_forecast(&self, id: &str, x: usize, y: usize, units: Option<ForecastUnits>, feature_flags: Option<&[&str]>) -> Result<Forecast> {
+        self.fetch(&request::gridpoints_forecast(id, x, y, units, feature_flags))
+    }
+    
+    pub fn gridpoints_forecast_hourly(&self, id: &str, x: usize, y: usize, units: Option<ForecastUnits>, feature_flags: Option<&[&str]>) -> Result<Forecast> {
+        self.fetch(&request::gridpoints_forecast_hourly(id, x, y, units, feature_flags))
     }
     
     pub fn gridpoints_stations(&self, id: &str, x: usize, y: usize) -> Result<GridpointStations> {
-        parse_result(get(&self.req, &format!("/gridpoints/{}/{},{}/stations", id, x, y), None))
+        self.fetch(&request::gridpoints_stations(id, x, y))
+    }
+    
+    pub fn radar_stations(&self, station_types: Option<&[&str]>, reporting_host: Option<&str>, host: Option<&str>) -> Result<RadarStationCollection> {
+        self.fetch(&request::radar_stations(station_types, reporting_host, host))
+    }
+    
+    pub fn radar_stations_id(&self, id: &str, reporting_host: Option<&str>, host: Option<&str>) -> Result<RadarStation> {
+        self.fetch(&request::radar_stations_id(id, reporting_host, host))
+    }
+    
+    pub fn radar_stations_alarms(&self, id: &str) -> Result<RadarStationAlarmCollection> {
+        self.fetch(&request::radar_stations_alarms(id))
+    }
+    
+    pub fn radar_servers(&self, reporting_host: Option<&str>) -> Result<RadarServerCollection> {
+        self.fetch(&request::radar_servers(reporting_host))
+    }
+    
+    pub fn radar_servers_id(&self, id: &str, reporting_host: Option<&str>) -> Result<RadarServer> {
+        self.fetch(&request::radar_servers_id(id, reporting_host))
+    }
+    
+    pub fn radar_queues(&self, host: &str, filter: &RadarQueueFilter) -> Result<RadarQueueCollection> {
+        self.fetch(&request::radar_queues(host, filter))
+    }
+    
+    /// `time` and `interval` are ISO 8601 interval and duration strings respectively.
+    pub fn radar_profilers(&self, id: &str, time: Option<&str>, interval: Option<&str>) -> Result<RadarProfiler> {
+        self.fetch(&request::radar_profilers(id, time, interval))
     }
     
     pub fn stations_observations(&self, id: &str, start: Option<DateTime<Utc>>, end: Option<DateTime<Utc>>, limit: Option<usize>) -> Result<ObservationCollection> {
-        let mut params = Vec::new();
-        if let Some(start) = start { params.push(("start", start.format(DATETIME_FMT).to_string())); }
-        if let Some(end) = end { params.push(("end", end.format(DATETIME_FMT).to_string())); }
-        if let Some(limit) = limit { params.push(("limit", max(1, min(500, limit)).to_string())); }
-        
-        parse_result(get(&self.req, &format!("/stations/{}/observations", id), Some(params)))
+        self.fetch(&request::stations_observations(id, start, end, limit))
     }
     
     pub fn stations_observations_latest(&self, id: &str, require_qc: Option<bool>) -> Result<Observation> {
-        let mut params = None;
-        if let Some(require_qc) = require_qc {
-            params = Some(vec![("require_qc", require_qc.to_string())]);
-        }
-        
-        parse_result(get(&self.req, &format!("/stations/{}/observations/latest", id), params))
+        self.fetch(&request::stations_observations_latest(id, require_qc))
+    }
+    
+    pub fn stations_tafs(&self, id: &str) -> Result<TafCollection> {
+        self.fetch(&request::stations_tafs(id))
+    }
+    
+    /// Returns the TAF issued at the given date and time as served by the API, which is an IWXXM (XML) document.
+    /// 
+    /// The traditional TAF text, as decoded by [`Taf::decode`](crate::endpoints::stations::taf::Taf::decode), is available
+    /// from the `TAF` text products, e.g. `latest_product("TAF", "IAD")`.
+    pub fn stations_tafs_time(&self, id: &str, date: NaiveDate, time: NaiveTime) -> Result<String> {
+        self.execute(&request::stations_tafs_time(id, date, time), ApiResponse::text)
     }
     
     pub fn stations_observations_time(&self, id: &str, time: DateTime<Utc>) -> Result<Observation> {
-        let time = time.format(DATETIME_FMT).to_string();
-        
-        parse_result(get(&self.req, &format!("/stations/{}/observations/{}", id, time), None))
+        self.fetch(&request::stations_observations_time(id, time))
     }
     
     pub fn stations(&self, ids: Option<&[&str]>, states: Option<&[&str]>, limit: Option<usize>) -> Result<ObservationStationCollection> {
-        let mut params = Vec::new();
-        if let Some(ids) = ids { params.push(("id", ids.join(","))); }
-        if let Some(states) = states { params.push(("state", states.join(","))); }
-        if let Some(limit) = limit { params.push(("limit", max(1, min(500, limit)).to_string())); }
-        
-        parse_result(get(&self.req, "/stations", Some(params)))
+        self.fetch(&request::stations(ids, states, limit))
     }
     
     pub fn stations_id(&self, id: &str) -> Result<ObservationStation> {
-        parse_result(get(&self.req, &format!("/stations/{}", id), None))
+        self.fetch(&request::stations_id(id))
     }
     
     pub fn points(&self, lat: f64, lon: f64) -> Result<Point> {
-        parse_result(get(&self.req, &format!("/points/{:.4},{:.4}", lat, lon), None))
+        self.fetch(&request::points(lat, lon))
+    }
+    
+    pub fn icons(&self) -> Result<IconCollection> {
+        self.fetch(&request::icons())
+    }
+    
+    /// Downloads the image for a single or dual-condition icon (`/icons/{set}/{timeOfDay}/{first}[/{second}]`).
+    pub fn icons_image(&self, set: IconSet, time_of_day: TimeOfDay, first: &IconCondition, second: Option<&IconCondition>, size: Option<IconSize>, font_size: Option<usize>) -> Result<Vec<u8>> {
+        self.execute(&request::icons_image(set, time_of_day, first, second, size, font_size), ApiResponse::bytes)
+    }
+    
+    /// Downloads the image behind an icon URL, such as [`Observation::icon`].
+    pub fn icons_url(&self, url: &Url) -> Result<Vec<u8>> {
+        self.execute(&request::icons_url(url), ApiResponse::bytes)
+    }
+    
+    pub fn offices(&self, id: &str) -> Result<Office> {
+        self.fetch(&request::offices(id))
+    }
+    
+    /// Follows an office URL, such as [`Point::forecast_office`] or [`Gridpoint::forecast_office`].
+    pub fn offices_url(&self, url: &Url) -> Result<Office> {
+        self.fetch(&ApiRequest::url(url))
+    }
+    
+    pub fn offices_headlines(&self, id: &str) -> Result<OfficeHeadlineCollection> {
+        self.fetch(&request::offices_headlines(id))
+    }
+    
+    pub fn offices_headlines_id(&self, id: &str, headline_id: &str) -> Result<OfficeHeadline> {
+        self.fetch(&request::offices_headlines_id(id, headline_id))
+    }
+    
+    pub fn products(&self, filter: &ProductFilter) -> Result<ProductCollection> {
+        self.fetch(&request::products(filter))
+    }
+    
+    pub fn products_id(&self, id: &str) -> Result<Product> {
+        self.fetch(&request::products_id(id))
+    }
+    
+    pub fn products_types(&self) -> Result<ProductTypeCollection> {
+        self.fetch(&request::products_types())
+    }
+    
+    pub fn products_types_id(&self, type_id: &str) -> Result<ProductCollection> {
+        self.fetch(&request::products_types_id(type_id))
+    }
+    
+    pub fn products_types_locations(&self, type_id: &str, location_id: &str) -> Result<ProductCollection> {
+        self.fetch(&request::products_types_locations(type_id, location_id))
+    }
+    
+    pub fn products_locations(&self) -> Result<ProductLocations> {
+        self.fetch(&request::products_locations())
+    }
+    
+    /// Most recent product of a type issued for a location, e.g. `latest_product("AFD", "LWX")` for the latest Area
+    /// Forecast Discussion from Sterling, VA.
+    pub fn latest_product(&self, type_id: &str, location_id: &str) -> Result<Product> {
+        self.fetch(&request::latest_product(type_id, location_id))
+    }
+    
+    pub fn zones(&self, filter: &ZoneFilter) -> Result<ZoneCollection> {
+        self.fetch(&request::zones(filter))
+    }
+    
+    pub fn zones_id(&self, zone_type: ZoneType, id: &str, effective: Option<DateTime<Utc>>) -> Result<Zone> {
+        self.fetch(&request::zones_id(zone_type, id, effective))
+    }
+    
+    /// Follows a zone URL, such as [`Point::forecast_zone`] or [`ObservationStation::county`].
+    pub fn zones_url(&self, url: &Url) -> Result<Zone> {
+        self.fetch(&ApiRequest::url(url))
+    }
+    
+    pub fn zones_forecast(&self, zone_type: ZoneType, id: &str) -> Result<ZoneForecast> {
+        self.fetch(&request::zones_forecast(zone_type, id))
+    }
+    
+    pub fn zones_observations(&self, id: &str, start: Option<DateTime<Utc>>, end: Option<DateTime<Utc>>, limit: Option<usize>) -> Result<ObservationCollection> {
+        self.fetch(&request::zones_observations(id, start, end, limit))
+    }
+    
+    pub fn zones_stations(&self, id: &str, limit: Option<usize>) -> Result<ObservationStationCollection> {
+        self.fetch(&request::zones_stations(id, limit))
+    }
+    
+    
+    /// Textual forecast for the grid cell containing the given coordinates.
+    /// 
+    /// Uses the `forecast` URL from the (cached) `/points` lookup.
+    pub fn forecast_at(&self, lat: f64, lon: f64) -> Result<Forecast> {
+        let point = self.cached_point(lat, lon)?;
+        
+        self.fetch(&ApiRequest::url(&point.forecast))
+    }
+    
+    /// Hourly forecast for the grid cell containing the given coordinates.
+    /// 
+    /// Uses the `forecastHourly` URL from the (cached) `/points` lookup.
+    pub fn hourly_forecast_at(&self, lat: f64, lon: f64) -> Result<Forecast> {
+        let point = self.cached_point(lat, lon)?;
+        
+        self.fetch(&ApiRequest::url(&point.forecast_hourly))
+    }
+    
+    /// Raw gridded forecast data for the grid cell containing the given coordinates.
+    /// 
+    /// Uses the `forecastGridData` URL from the (cached) `/points` lookup.
+    pub fn grid_data_at(&self, lat: f64, lon: f64) -> Result<Gridpoint> {
+        let point = self.cached_point(lat, lon)?;
+        
+        self.fetch(&ApiRequest::url(&point.forecast_grid_data))
+    }
+    
+    /// Latest observation from the closest station to the given coordinates.
+    /// 
+    /// Stations are taken from the `observationStations` URL of the (cached) `/points` lookup, which lists them by
+    /// distance. Returns `None` if no station is associated with the point.
+    pub fn nearest_observation_at(&self, lat: f64, lon: f64) -> Result<Option<Observation>> {
+        let point = self.cached_point(lat, lon)?;
+        let stations: ObservationStationCollection = self.fetch(&ApiRequest::url(&point.observation_stations))?;
+        
+        match stations.graph.first() {
+            Some(station) => self.fetch(&ApiRequest::url(format!("{}/observations/latest", station.id))).map(Some),
+            None => Ok(None)
+        }
+    }
+    
+    /// Forgets all `/points` lookups made by the `*_at` helpers.
+    pub fn clear_point_cache(&self) {
+        self.point_cache.clear();
+    }
+    
+    /// Point metadata rarely changes, so it is only requested once per coordinate pair (at the precision sent to the API).
+    fn cached_point(&self, lat: f64, lon: f64) -> Result<Point> {
+        if let Some(point) = self.point_cache.get(lat, lon) {
+            return Ok(point);
+        }
+        
+        let point = self.points(lat, lon)?;
+        self.point_cache.insert(lat, lon, point.clone());
+        
+        Ok(point)
     }
     
     
     pub fn radar(&self, id: &str, radar_type: RadarType) -> Result<Vec<RemoteFile>> {
-        crawl(&self.req, format!("https://mrms.ncep.noaa.gov/data/RIDGEII/L2/{}/{}/", id, radar_type))
+        let request = request::radar(id, radar_type);
+        let url = self.pipeline.rebased(&request).url;
+        
+        self.execute(&request, |resp| parse_listing(&url, &resp.text()?))
     }
-}
-
-fn get(req: &ReqClient, endpoint: &str, params: Option<Vec<(&str, String)>>) -> Result<Response> {
-    let mut req = req.get(format!("{}{}", ROOT, endpoint))
-        .header("Content-Type", "application/ld+json")
-        .header("Accept", "application/ld+json");
-    if let Some(params) = params {
-        req = req.query(&params);
+    
+    
+    pub(crate) fn fetch<T: DeserializeOwned>(&self, request: &ApiRequest) -> Result<T> {
+        self.execute(request, ApiResponse::json)
     }
     
-    match req.send() {
-        Ok(resp) => Ok(resp),
-        Err(err) => Err(err.into())
+    fn execute<T>(&self, request: &ApiRequest, read: impl Fn(ApiResponse) -> Result<T>) -> Result<T> {
+        Ok(self.execute_cached(request, read)?.value)
+    }
+    
+    fn execute_cached<T>(&self, request: &ApiRequest, read: impl Fn(ApiResponse) -> Result<T>) -> Result<Cached<T>> {
+        block_on(self.pipeline.execute(
+            request,
+            read,
+            |request| ready(self.transport.send(&request)),
+            |delay| {
+                std::thread::sleep(delay);
+                ready(())
+            },
+        ))
     }
 }
-
-fn parse_result<T: DeserializeOwned>(resp: Result<Response>) -> Result<T> {
-    let resp = match resp {
-        Ok(resp) => match resp.text() {
-            Ok(text) => text,
-            Err(err) => return Err(err.into())
-        },
-        Err(err) => return Err(err)
-    };
-    
-    match serde_json::from_str(&resp) {
-        Ok(ok) => Ok(ok),
-        Err(err) => Err(err.into())
-    }
-}
\ No newline at end of file
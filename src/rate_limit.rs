@@ -0,0 +1,68 @@
+//! Client-side rate limiting.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+
+/// Token bucket settings: a steady rate with room for short bursts.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RateLimit {
+    /// Steady request rate. Zero or less disables limiting.
+    pub requests_per_second: f64,
+    /// Requests that may be sent back to back after a quiet period. At least 1.
+    pub burst: u32,
+}
+impl RateLimit {
+    pub fn new(requests_per_second: f64, burst: u32) -> Self { Self {
+        requests_per_second,
+        burst,
+    }}
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Token bucket shared by every clone of a client.
+/// 
+/// Callers reserve a token with [`acquire`](Self::acquire) and then wait the returned duration themselves, so the same
+/// limiter works for blocking and async clients and the lock is never held while waiting.
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    limit: RateLimit,
+    bucket: Mutex<Bucket>,
+}
+impl RateLimiter {
+    pub fn new(limit: RateLimit) -> Self { Self {
+        limit,
+        bucket: Mutex::new(Bucket { tokens: limit.burst.max(1) as f64, updated: Instant::now() }),
+    }}
+    
+    /// Takes a token and returns how long to wait before sending the request it pays for.
+    /// 
+    /// Tokens may be borrowed ahead of time, so concurrent callers queue up one interval apart instead of racing.
+    pub fn acquire(&self) -> Duration {
+        let rate = self.limit.requests_per_second;
+        if rate <= 0.0 || !rate.is_finite() {
+            return Duration::ZERO;
+        }
+        let mut bucket = match self.bucket.lock() {
+            Ok(bucket) => bucket,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        
+        let now = Instant::now();
+        let refill = now.duration_since(bucket.updated).as_secs_f64() * rate;
+        bucket.tokens = (bucket.tokens + refill).min(self.limit.burst.max(1) as f64);
+        bucket.updated = now;
+        
+        bucket.tokens -= 1.0;
+        if bucket.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-bucket.tokens / rate)
+        }
+    }
+}
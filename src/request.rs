@@ -0,0 +1,606 @@
+//! Request building and response parsing shared by [`NwsClient`](crate::NwsClient) and the async client.
+//!
+//! Every endpoint is described once here as an [`ApiRequest`]. The clients only differ in how they send it.
+
+use std::cmp::{max, min};
+use std::future::Future;
+use std::pin::pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake};
+use std::thread::{self, Thread};
+use std::time::Duration;
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use serde::de::DeserializeOwned;
+use url::Url;
+use crate::{NwsError, ProblemDetail, Result, DATETIME_FMT, MRMS_ROOT, ROOT};
+use crate::cache::{CacheStatus, Cached, LastCacheStatus, Lookup, ResponseCache};
+use crate::rate_limit::RateLimiter;
+use crate::retry::RetryPolicy;
+use crate::endpoints::alerts::AlertFilter;
+use crate::endpoints::gridpoints::ForecastUnits;
+use crate::endpoints::icons::{IconCondition, IconSet, IconSize, TimeOfDay};
+use crate::endpoints::products::ProductFilter;
+use crate::endpoints::radar::{RadarQueueFilter, RadarType};
+use crate::endpoints::zones::{ZoneFilter, ZoneType};
+
+const LD_JSON: &str = "application/ld+json";
+
+
+/// A GET request, independent of the HTTP client used to send it.
+/// 
+/// This is what a [`Transport`](crate::transport::Transport) is asked to send.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ApiRequest {
+    /// Absolute URL, without the query string.
+    pub url: String,
+    /// Query parameters, in the order they should be sent.
+    pub params: Vec<(&'static str, String)>,
+    pub headers: Vec<(&'static str, String)>,
+    /// The service the request is for, which decides the rate limit it counts against.
+    pub service: Service,
+}
+impl ApiRequest {
+    /// A JSON-LD request to an API endpoint, relative to [`ROOT`].
+    pub(crate) fn endpoint(endpoint: impl AsRef<str>) -> Self {
+        Self::url(format!("{}{}", ROOT, endpoint.as_ref()))
+    }
+    
+    /// A JSON-LD request to an absolute URL, such as one returned by the API itself.
+    pub(crate) fn url(url: impl AsRef<str>) -> Self { Self {
+        url: url.as_ref().to_owned(),
+        params: vec![],
+        headers: vec![("Content-Type", LD_JSON.to_owned()), ("Accept", LD_JSON.to_owned())],
+        service: Service::Api,
+    }}
+    
+    pub(crate) fn params(mut self, params: Vec<(&'static str, String)>) -> Self {
+        self.params.extend(params);
+        self
+    }
+    
+    /// The URL with the query parameters applied.
+    pub fn full_url(&self) -> Option<Url> {
+        if self.params.is_empty() {
+            Url::parse(&self.url).ok()
+        } else {
+            Url::parse_with_params(&self.url, &self.params).ok()
+        }
+    }
+    
+    /// Sets a header, replacing any previous value for the same name.
+    pub(crate) fn header(mut self, name: &'static str, value: impl Into<String>) -> Self {
+        self.headers.retain(|(existing, _)| !existing.eq_ignore_ascii_case(name));
+        self.headers.push((name, value.into()));
+        self
+    }
+    
+    /// Moves the request from the host at `from` to `to`, e.g. from [`ROOT`] to a local stub server. URLs under any
+    /// other host, or merely sharing a prefix with `from` such as `https://api.weather.gov.example`, are left alone.
+    pub(crate) fn rebase(mut self, from: &str, to: &str) -> Self {
+        if from != to {
+            if let Some(rest) = self.url.strip_prefix(from).filter(|rest| rest.is_empty() || rest.starts_with('/')) {
+                self.url = format!("{}{}", to, rest);
+            }
+        }
+        self
+    }
+}
+
+/// The services requests are sent to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Service {
+    /// The NWS API at [`ROOT`].
+    Api,
+    /// The MRMS radar directories at [`MRMS_ROOT`], crawled by `radar`.
+    Mrms,
+}
+
+/// A response, independent of the HTTP client that received it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ApiResponse {
+    /// HTTP status code.
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+impl ApiResponse {
+    pub fn new(status: u16, body: impl Into<Vec<u8>>) -> Self { Self {
+        status,
+        headers: vec![],
+        body: body.into(),
+    }}
+    
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+    
+    /// First value of a header, matched case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(existing, _)| existing.eq_ignore_ascii_case(name)).map(|(_, value)| value.as_str())
+    }
+    
+    /// How long the server asked to wait before retrying, from a `Retry-After` header in seconds or as an HTTP date.
+    pub fn retry_after(&self) -> Option<Duration> {
+        let value = self.header("Retry-After")?.trim();
+        if let Ok(seconds) = value.parse() {
+            return Some(Duration::from_secs(seconds));
+        }
+        let date = DateTime::parse_from_rfc2822(value).ok()?;
+        
+        Some((date.with_timezone(&Utc) - Utc::now()).to_std().unwrap_or_default())
+    }
+    
+    /// Turns error statuses into the matching [`NwsError`], reading the problem document if there is one.
+    pub(crate) fn check(self) -> Result<Self> {
+        if (200..300).contains(&self.status) {
+            return Ok(self);
+        }
+        
+        let problem = serde_json::from_slice::<ProblemDetail>(&self.body).ok().map(|mut problem| {
+            if problem.status == 0 { problem.status = self.status; }
+            Box::new(problem)
+        });
+        Err(match (self.status, problem) {
+            (429, problem) => NwsError::RateLimited { retry_after: self.retry_after(), problem },
+            (400, Some(problem)) => NwsError::InvalidParameters(problem),
+            (_, Some(problem)) => NwsError::Api(problem),
+            (status, None) => NwsError::Http { status, body: String::from_utf8_lossy(&self.body).into_owned() },
+        })
+    }
+    
+    pub(crate) fn json<T: DeserializeOwned>(self) -> Result<T> {
+        Ok(serde_json::from_slice(&self.check()?.body)?)
+    }
+    
+    pub(crate) fn text(self) -> Result<String> {
+        Ok(String::from_utf8_lossy(&self.check()?.body).into_owned())
+    }
+    
+    pub(crate) fn bytes(self) -> Result<Vec<u8>> {
+        Ok(self.check()?.body)
+    }
+}
+
+
+/// Where a client sends its requests and how it caches, throttles and retries them. Shared by both clients, which
+/// only supply the functions that send a request and wait.
+#[derive(Clone, Debug)]
+pub(crate) struct Pipeline {
+    pub base_url: String,
+    pub mrms_url: String,
+    pub retry_policy: RetryPolicy,
+    pub rate_limiter: Option<Arc<RateLimiter>>,
+    pub mrms_rate_limiter: Option<Arc<RateLimiter>>,
+    pub cache: Option<Arc<ResponseCache>>,
+    pub last_cache_status: LastCacheStatus,
+}
+impl Default for Pipeline {
+    fn default() -> Self { Self {
+        base_url: ROOT.to_owned(),
+        mrms_url: MRMS_ROOT.to_owned(),
+        retry_policy: RetryPolicy::default(),
+        rate_limiter: None,
+        mrms_rate_limiter: None,
+        cache: None,
+        last_cache_status: LastCacheStatus::default(),
+    }}
+}
+impl Pipeline {
+    /// `request` moved onto the configured host of its service.
+    pub fn rebased(&self, request: &ApiRequest) -> ApiRequest {
+        match request.service {
+            Service::Api => request.clone().rebase(ROOT, &self.base_url),
+            Service::Mrms => request.clone().rebase(MRMS_ROOT, &self.mrms_url),
+        }
+    }
+    
+    /// Sends `request` with `send` and reads the response with `read`, going through the response cache if there is
+    /// one, waiting for the rate limiter and retrying failures as allowed by the retry policy. All waiting is done
+    /// with `sleep`. The cache outcome is also kept as the last cache status, which is cleared if the request fails.
+    pub async fn execute<T, S, SF, W, WF>(&self, request: &ApiRequest, read: impl Fn(ApiResponse) -> Result<T>, send: S, sleep: W) -> Result<Cached<T>>
+    where
+        S: Fn(ApiRequest) -> SF,
+        SF: Future<Output = Result<ApiResponse>>,
+        W: Fn(Duration) -> WF,
+        WF: Future<Output = ()>,
+    {
+        let result = self.execute_with_retries(request, read, send, sleep).await;
+        self.last_cache_status.set(result.as_ref().ok().map(|cached| cached.cache_status));
+        
+        result
+    }
+    
+    async fn execute_with_retries<T, S, SF, W, WF>(&self, request: &ApiRequest, read: impl Fn(ApiResponse) -> Result<T>, send: S, sleep: W) -> Result<Cached<T>>
+    where
+        S: Fn(ApiRequest) -> SF,
+        SF: Future<Output = Result<ApiResponse>>,
+        W: Fn(Duration) -> WF,
+        WF: Future<Output = ()>,
+    {
+        let request = self.rebased(request);
+        let (request, stale) = match self.cache.as_ref().map(|cache| cache.lookup(&request)) {
+            Some(Lookup::Fresh(resp)) => return Ok(Cached { value: read(resp)?, cache_status: CacheStatus::Hit }),
+            Some(Lookup::Send { request, stale }) => (request, stale),
+            None => (request, None),
+        };
+        let rate_limiter = match request.service {
+            Service::Api => &self.rate_limiter,
+            Service::Mrms => &self.mrms_rate_limiter,
+        };
+        
+        let mut attempt = 1;
+        loop {
+            if let Some(rate_limiter) = rate_limiter {
+                sleep(rate_limiter.acquire()).await;
+            }
+            let resp = send(request.clone()).await;
+            let retry_after = resp.as_ref().ok().and_then(ApiResponse::retry_after);
+            let resp = resp.map(|resp| match &self.cache {
+                Some(cache) => cache.store(&request, stale.clone(), resp),
+                None => (resp, CacheStatus::Bypass),
+            });
+            match resp.and_then(|(resp, cache_status)| Ok(Cached { value: read(resp)?, cache_status })) {
+                Err(err) => match self.retry_policy.delay(attempt, &err, retry_after) {
+                    Some(delay) => sleep(delay).await,
+                    None => return Err(err),
+                },
+                result => return result,
+            }
+            attempt += 1;
+        }
+    }
+}
+
+/// Runs a future to completion on the current thread. The blocking client uses this to drive [`Pipeline::execute`]
+/// with send and sleep functions that block, so its futures are ready the first time they are polled.
+pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+    struct Unpark(Thread);
+    impl Wake for Unpark {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+    
+    let mut future = pin!(future);
+    let waker = Arc::new(Unpark(thread::current())).into();
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+        thread::park();
+    }
+}
+
+
+
+fn fmt_date(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+
+pub(crate) fn alerts(start: Option<DateTime<Utc>>, end: Option<DateTime<Utc>>, filter: &AlertFilter, cursor: Option<&str>) -> ApiRequest {
+    let mut params = Vec::new();
+    if let Some(start) = start { params.push(("start", start.format(DATETIME_FMT).to_string())); }
+    if let Some(end) = end { params.push(("end", end.format(DATETIME_FMT).to_string())); }
+    params.extend(filter.params());
+    if let Some(cursor) = cursor { params.push(("cursor", cursor.to_owned())); }
+    
+    ApiRequest::endpoint("/alerts").params(params)
+}
+
+pub(crate) fn alerts_active(filter: &AlertFilter) -> ApiRequest {
+    ApiRequest::endpoint("/alerts/active").params(filter.params())
+}
+
+pub(crate) fn alerts_active_count() -> ApiRequest {
+    ApiRequest::endpoint("/alerts/active/count")
+}
+
+pub(crate) fn alerts_active_zone(zone_id: &str) -> ApiRequest {
+    ApiRequest::endpoint(format!("/alerts/active/zone/{}", zone_id))
+}
+
+pub(crate) fn alerts_active_area(area: &str) -> ApiRequest {
+    ApiRequest::endpoint(format!("/alerts/active/area/{}", area))
+}
+
+pub(crate) fn alerts_active_region(region: &str) -> ApiRequest {
+    ApiRequest::endpoint(format!("/alerts/active/region/{}", region))
+}
+
+pub(crate) fn alerts_types() -> ApiRequest {
+    ApiRequest::endpoint("/alerts/types")
+}
+
+pub(crate) fn alerts_id(id: &str) -> ApiRequest {
+    ApiRequest::endpoint(format!("/alerts/{}", id))
+}
+
+pub(crate) fn aviation_cwsus(id: &str) -> ApiRequest {
+    ApiRequest::endpoint(format!("/aviation/cwsus/{}", id))
+}
+
+pub(crate) fn aviation_cwsus_cwas(id: &str) -> ApiRequest {
+    ApiRequest::endpoint(format!("/aviation/cwsus/{}/cwas", id))
+}
+
+pub(crate) fn aviation_cwsus_cwas_id(id: &str, date: NaiveDate, sequence: usize) -> ApiRequest {
+    ApiRequest::endpoint(format!("/aviation/cwsus/{}/cwas/{}/{}", id, fmt_date(date), sequence))
+}
+
+pub(crate) fn aviation_sigmets(start: Option<DateTime<Utc>>, end: Option<DateTime<Utc>>, date: Option<NaiveDate>, atsu: Option<&str>, sequence: Option<&str>) -> ApiRequest {
+    let mut params = Vec::new();
+    if let Some(start) = start { params.push(("start", start.format(DATETIME_FMT).to_string())); }
+    if let Some(end) = end { params.push(("end", end.format(DATETIME_FMT).to_string())); }
+    if let Some(date) = date { params.push(("date", fmt_date(date))); }
+    if let Some(atsu) = atsu { params.push(("atsu", atsu.to_owned())); }
+    if let Some(sequence) = sequence { params.push(("sequence", sequence.to_owned())); }
+    
+    ApiRequest::endpoint("/aviation/sigmets").params(params)
+}
+
+pub(crate) fn aviation_sigmets_atsu(atsu: &str) -> ApiRequest {
+    ApiRequest::endpoint(format!("/aviation/sigmets/{}", atsu))
+}
+
+pub(crate) fn aviation_sigmets_atsu_date(atsu: &str, date: NaiveDate) -> ApiRequest {
+    ApiRequest::endpoint(format!("/aviation/sigmets/{}/{}", atsu, fmt_date(date)))
+}
+
+pub(crate) fn aviation_sigmets_atsu_date_time(atsu: &str, date: NaiveDate, time: NaiveTime) -> ApiRequest {
+    ApiRequest::endpoint(format!("/aviation/sigmets/{}/{}/{}", atsu, fmt_date(date), time.format("%H%M")))
+}
+
+pub(crate) fn glossary() -> ApiRequest {
+    ApiRequest::endpoint("/glossary")
+}
+
+pub(crate) fn gridpoints(id: &str, x: usize, y: usize) -> ApiRequest {
+    ApiRequest::endpoint(format!("/gridpoints/{}/{},{}", id, x, y))
+}
+
+fn forecast(endpoint: String, units: Option<ForecastUnits>, feature_flags: Option<&[&str]>) -> ApiRequest {
+    let mut request = ApiRequest::endpoint(endpoint);
+    if let Some(units) = units { request = request.params(vec![("units", units.to_string())]); }
+    if let Some(flags) = feature_flags { request = request.header("Feature-Flags", flags.join(",")); }
+    
+    request
+}
+
+pub(crate) fn gridpoints_forecast(id: &str, x: usize, y: usize, units: Option<ForecastUnits>, feature_flags: Option<&[&str]>) -> ApiRequest {
+    forecast(format!("/gridpoints/{}/{},{}/forecast", id, x, y), units, feature_flags)
+}
+
+pub(crate) fn gridpoints_forecast_hourly(id: &str, x: usize, y: usize, units: Option<ForecastUnits>, feature_flags: Option<&[&str]>) -> ApiRequest {
+    forecast(format!("/gridpoints/{}/{},{}/forecast/hourly", id, x, y), units, feature_flags)
+}
+
+pub(crate) fn gridpoints_stations(id: &str, x: usize, y: usize) -> ApiRequest {
+    ApiRequest::endpoint(format!("/gridpoints/{}/{},{}/stations", id, x, y))
+}
+
+pub(crate) fn radar_stations(station_types: Option<&[&str]>, reporting_host: Option<&str>, host: Option<&str>) -> ApiRequest {
+    let mut params = Vec::new();
+    if let Some(station_types) = station_types { params.push(("stationType", station_types.join(","))); }
+    if let Some(reporting_host) = reporting_host { params.push(("reportingHost", reporting_host.to_owned())); }
+    if let Some(host) = host { params.push(("host", host.to_owned())); }
+    
+    ApiRequest::endpoint("/radar/stations").params(params)
+}
+
+pub(crate) fn radar_stations_id(id: &str, reporting_host: Option<&str>, host: Option<&str>) -> ApiRequest {
+    let mut params = Vec::new();
+    if let Some(reporting_host) = reporting_host { params.push(("reportingHost", reporting_host.to_owned())); }
+    if let Some(host) = host { params.push(("host", host.to_owned())); }
+    
+    ApiRequest::endpoint(format!("/radar/stations/{}", id)).params(params)
+}
+
+pub(crate) fn radar_stations_alarms(id: &str) -> ApiRequest {
+    ApiRequest::endpoint(format!("/radar/stations/{}/alarms", id))
+}
+
+pub(crate) fn radar_servers(reporting_host: Option<&str>) -> ApiRequest {
+    let params = reporting_host.map(|reporting_host| vec![("reportingHost", reporting_host.to_owned())]);
+    
+    ApiRequest::endpoint("/radar/servers").params(params.unwrap_or_default())
+}
+
+pub(crate) fn radar_servers_id(id: &str, reporting_host: Option<&str>) -> ApiRequest {
+    let params = reporting_host.map(|reporting_host| vec![("reportingHost", reporting_host.to_owned())]);
+    
+    ApiRequest::endpoint(format!("/radar/servers/{}", id)).params(params.unwrap_or_default())
+}
+
+pub(crate) fn radar_queues(host: &str, filter: &RadarQueueFilter) -> ApiRequest {
+    ApiRequest::endpoint(format!("/radar/queues/{}", host)).params(filter.params())
+}
+
+pub(crate) fn radar_profilers(id: &str, time: Option<&str>, interval: Option<&str>) -> ApiRequest {
+    let mut params = Vec::new();
+    if let Some(time) = time { params.push(("time", time.to_owned())); }
+    if let Some(interval) = interval { params.push(("interval", interval.to_owned())); }
+    
+    ApiRequest::endpoint(format!("/radar/profilers/{}", id)).params(params)
+}
+
+pub(crate) fn stations_observations(id: &str, start: Option<DateTime<Utc>>, end: Option<DateTime<Utc>>, limit: Option<usize>) -> ApiRequest {
+    let mut params = Vec::new();
+    if let Some(start) = start { params.push(("start", start.format(DATETIME_FMT).to_string())); }
+    if let Some(end) = end { params.push(("end", end.format(DATETIME_FMT).to_string())); }
+    if let Some(limit) = limit { params.push(("limit", max(1, min(500, limit)).to_string())); }
+    
+    ApiRequest::endpoint(format!("/stations/{}/observations", id)).params(params)
+}
+
+pub(crate) fn stations_observations_latest(id: &str, require_qc: Option<bool>) -> ApiRequest {
+    let mut params = Vec::new();
+    if let Some(require_qc) = require_qc { params.push(("require_qc", require_qc.to_string())); }
+    
+    ApiRequest::endpoint(format!("/stations/{}/observations/latest", id)).params(params)
+}
+
+pub(crate) fn stations_tafs(id: &str) -> ApiRequest {
+    ApiRequest::endpoint(format!("/stations/{}/tafs", id))
+}
+
+pub(crate) fn stations_tafs_time(id: &str, date: NaiveDate, time: NaiveTime) -> ApiRequest {
+    ApiRequest::endpoint(format!("/stations/{}/tafs/{}/{}", id, fmt_date(date), time.format("%H%M")))
+}
+
+pub(crate) fn stations_observations_time(id: &str, time: DateTime<Utc>) -> ApiRequest {
+    ApiRequest::endpoint(format!("/stations/{}/observations/{}", id, time.format(DATETIME_FMT)))
+}
+
+pub(crate) fn stations(ids: Option<&[&str]>, states: Option<&[&str]>, limit: Option<usize>) -> ApiRequest {
+    let mut params = Vec::new();
+    if let Some(ids) = ids { params.push(("id", ids.join(","))); }
+    if let Some(states) = states { params.push(("state", states.join(","))); }
+    if let Some(limit) = limit { params.push(("limit", max(1, min(500, limit)).to_string())); }
+    
+    ApiRequest::endpoint("/stations").params(params)
+}
+
+pub(crate) fn stations_id(id: &str) -> ApiRequest {
+    ApiRequest::endpoint(format!("/stations/{}", id))
+}
+
+pub(crate) fn points(lat: f64, lon: f64) -> ApiRequest {
+    ApiRequest::endpoint(format!("/points/{:.4},{:.4}", lat, lon))
+}
+
+pub(crate) fn icons() -> ApiRequest {
+    ApiRequest::endpoint("/icons")
+}
+
+pub(crate) fn icons_image(set: IconSet, time_of_day: TimeOfDay, first: &IconCondition, second: Option<&IconCondition>, size: Option<IconSize>, font_size: Option<usize>) -> ApiRequest {
+    let mut endpoint = format!("/icons/{}/{}/{}", set, time_of_day, first);
+    if let Some(second) = second {
+        endpoint = format!("{}/{}", endpoint, second);
+    }
+    let mut params = Vec::new();
+    if let Some(size) = size { params.push(("size", size.to_string())); }
+    if let Some(font_size) = font_size { params.push(("fontsize", font_size.to_string())); }
+    
+    image(ApiRequest::endpoint(endpoint).params(params))
+}
+
+pub(crate) fn icons_url(url: &Url) -> ApiRequest {
+    image(ApiRequest::url(url))
+}
+
+fn image(request: ApiRequest) -> ApiRequest {
+    let mut request = request.header("Accept", "image/png");
+    request.headers.retain(|(name, _)| *name != "Content-Type");
+    
+    request
+}
+
+pub(crate) fn offices(id: &str) -> ApiRequest {
+    ApiRequest::endpoint(format!("/offices/{}", id))
+}
+
+pub(crate) fn offices_headlines(id: &str) -> ApiRequest {
+    ApiRequest::endpoint(format!("/offices/{}/headlines", id))
+}
+
+pub(crate) fn offices_headlines_id(id: &str, headline_id: &str) -> ApiRequest {
+    ApiRequest::endpoint(format!("/offices/{}/headlines/{}", id, headline_id))
+}
+
+pub(crate) fn products(filter: &ProductFilter) -> ApiRequest {
+    ApiRequest::endpoint("/products").params(filter.params())
+}
+
+pub(crate) fn products_id(id: &str) -> ApiRequest {
+    ApiRequest::endpoint(format!("/products/{}", id))
+}
+
+pub(crate) fn products_types() -> ApiRequest {
+    ApiRequest::endpoint("/products/types")
+}
+
+pub(crate) fn products_types_id(type_id: &str) -> ApiRequest {
+    ApiRequest::endpoint(format!("/products/types/{}", type_id))
+}
+
+pub(crate) fn products_types_locations(type_id: &str, location_id: &str) -> ApiRequest {
+    ApiRequest::endpoint(format!("/products/types/{}/locations/{}", type_id, location_id))
+}
+
+pub(crate) fn products_locations() -> ApiRequest {
+    ApiRequest::endpoint("/products/locations")
+}
+
+pub(crate) fn latest_product(type_id: &str, location_id: &str) -> ApiRequest {
+    ApiRequest::endpoint(format!("/products/types/{}/locations/{}/latest", type_id, location_id))
+}
+
+pub(crate) fn zones(filter: &ZoneFilter) -> ApiRequest {
+    ApiRequest::endpoint("/zones").params(filter.params())
+}
+
+pub(crate) fn zones_id(zone_type: ZoneType, id: &str, effective: Option<DateTime<Utc>>) -> ApiRequest {
+    let params = effective.map(|effective| vec![("effective", effective.format(DATETIME_FMT).to_string())]);
+    
+    ApiRequest::endpoint(format!("/zones/{}/{}", zone_type, id)).params(params.unwrap_or_default())
+}
+
+pub(crate) fn zones_forecast(zone_type: ZoneType, id: &str) -> ApiRequest {
+    ApiRequest::endpoint(format!("/zones/{}/{}/forecast", zone_type, id))
+}
+
+pub(crate) fn zones_observations(id: &str, start: Option<DateTime<Utc>>, end: Option<DateTime<Utc>>, limit: Option<usize>) -> ApiRequest {
+    let mut params = Vec::new();
+    if let Some(start) = start { params.push(("start", start.format(DATETIME_FMT).to_string())); }
+    if let Some(end) = end { params.push(("end", end.format(DATETIME_FMT).to_string())); }
+    if let Some(limit) = limit { params.push(("limit", max(1, min(500, limit)).to_string())); }
+    
+    ApiRequest::endpoint(format!("/zones/forecast/{}/observations", id)).params(params)
+}
+
+pub(crate) fn zones_stations(id: &str, limit: Option<usize>) -> ApiRequest {
+    let params = limit.map(|limit| vec![("limit", max(1, min(500, limit)).to_string())]);
+    
+    ApiRequest::endpoint(format!("/zones/forecast/{}/stations", id)).params(params.unwrap_or_default())
+}
+
+/// The directory listing of an MRMS radar product. This is an HTML page, not part of the NWS API.
+pub(crate) fn radar(id: &str, radar_type: RadarType) -> ApiRequest {
+    ApiRequest {
+        url: format!("{}/data/RIDGEII/L2/{}/{}/", MRMS_ROOT, id, radar_type),
+        params: vec![],
+        headers: vec![],
+        service: Service::Mrms,
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn rebases_at_path_boundary() {
+        let rebase = |url: &str| ApiRequest::url(url).rebase(ROOT, "http://localhost:8080").url;
+        
+        assert_eq!(rebase("https://api.weather.gov/points/35,-97"), "http://localhost:8080/points/35,-97");
+        assert_eq!(rebase("https://api.weather.gov"), "http://localhost:8080");
+        assert_eq!(rebase("https://api.weather.gov.example/points"), "https://api.weather.gov.example/points");
+        assert_eq!(rebase("https://api.weather.govx/points"), "https://api.weather.govx/points");
+    }
+    
+    #[test]
+    fn rebases_by_service() {
+        let pipeline = Pipeline {
+            base_url: "http://localhost:8080".to_owned(),
+            mrms_url: format!("{}/mirror", MRMS_ROOT),
+            ..Pipeline::default()
+        };
+        
+        assert_eq!(pipeline.rebased(&glossary()).url, "http://localhost:8080/glossary");
+        assert_eq!(pipeline.rebased(&radar("KTLX", RadarType::BrefRaw)).url, "https://mrms.ncep.noaa.gov/mirror/data/RIDGEII/L2/KTLX/BREF_RAW/");
+    }
+}
@@ -0,0 +1,134 @@
+//! Retrying failed requests.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+use crate::{ErrorKind, NwsError};
+
+
+/// When and how long to wait before sending a failed request again.
+/// 
+/// An error is retried if its HTTP status is in `retry_statuses` or its kind is in `retry_kinds`, until
+/// `max_attempts` requests have been made. The wait doubles (by `multiplier`) after each attempt, up to `max_backoff`.
+/// 
+/// The default retries 429 and 5xx gateway/server errors and connection failures, three attempts in total.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first. `1` disables retrying.
+    pub max_attempts: u32,
+    /// Wait before the first retry.
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// Growth of the wait per attempt. Non-finite results wait `max_backoff`, negative ones not at all.
+    pub multiplier: f64,
+    /// Randomly shortens each wait by up to half, so that clients failing together don't retry together.
+    pub jitter: bool,
+    /// Waits as long as a `Retry-After` header asks instead of the computed backoff.
+    pub honor_retry_after: bool,
+    /// Gives up instead of retrying if `Retry-After` asks to wait longer than this.
+    pub max_retry_after: Duration,
+    pub retry_statuses: Vec<u16>,
+    pub retry_kinds: Vec<ErrorKind>,
+}
+impl Default for RetryPolicy {
+    fn default() -> Self { Self {
+        max_attempts: 3,
+        initial_backoff: Duration::from_millis(500),
+        max_backoff: Duration::from_secs(10),
+        multiplier: 2.0,
+        jitter: true,
+        honor_retry_after: true,
+        max_retry_after: Duration::from_secs(60),
+        retry_statuses: vec![429, 500, 502, 503, 504],
+        retry_kinds: vec![ErrorKind::Transport],
+    }}
+}
+impl RetryPolicy {
+    /// Policy making a single attempt.
+    pub fn none() -> Self { Self {
+        max_attempts: 1,
+        ..Self::default()
+    }}
+    
+    pub fn is_retryable(&self, err: &NwsError) -> bool {
+        err.status().is_some_and(|status| self.retry_statuses.contains(&status)) || self.retry_kinds.contains(&err.kind())
+    }
+    
+    /// How long to wait before the next attempt, after attempt number `attempt` (starting at 1) failed with `err`.
+    /// 
+    /// `retry_after` is the server's `Retry-After`, if it sent one. `None` means the error should be returned.
+    pub fn delay(&self, attempt: u32, err: &NwsError, retry_after: Option<Duration>) -> Option<Duration> {
+        if attempt >= self.max_attempts || !self.is_retryable(err) {
+            return None;
+        }
+        
+        match retry_after.filter(|_| self.honor_retry_after) {
+            Some(retry_after) if retry_after > self.max_retry_after => None,
+            Some(retry_after) => Some(retry_after),
+            None => Some(self.backoff(attempt)),
+        }
+    }
+    
+    fn backoff(&self, attempt: u32) -> Duration {
+        let max_backoff = self.max_backoff.as_secs_f64();
+        let backoff = self.initial_backoff.as_secs_f64() * self.multiplier.powi(attempt as i32 - 1);
+        // A NaN multiplier, or an infinite one times a zero initial backoff, waits as long as allowed.
+        let backoff = if backoff.is_nan() { max_backoff } else { backoff.clamp(0.0, max_backoff) };
+        let backoff = if self.jitter { backoff * (0.5 + random_fraction() / 2.0) } else { backoff };
+        
+        Duration::try_from_secs_f64(backoff).unwrap_or(self.max_backoff)
+    }
+}
+
+/// A number in `[0, 1)`, random enough to spread retries out.
+fn random_fraction() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_nanos());
+    
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn policy(initial_backoff: Duration, multiplier: f64, max_backoff: Duration) -> RetryPolicy {
+        RetryPolicy { initial_backoff, multiplier, max_backoff, jitter: false, max_attempts: 10, ..RetryPolicy::default() }
+    }
+    
+    #[test]
+    fn backs_off_exponentially_up_to_max() {
+        let policy = policy(Duration::from_millis(500), 2.0, Duration::from_secs(3));
+        let backoffs: Vec<Duration> = (1..=5).map(|attempt| policy.backoff(attempt)).collect();
+        
+        assert_eq!(backoffs, [500, 1000, 2000, 3000, 3000].map(Duration::from_millis));
+    }
+    
+    #[test]
+    fn jitter_shortens_by_up_to_half() {
+        let policy = RetryPolicy { jitter: true, ..policy(Duration::from_secs(2), 1.0, Duration::from_secs(10)) };
+        let backoff = policy.backoff(1);
+        
+        assert!(backoff >= Duration::from_secs(1) && backoff <= Duration::from_secs(2), "{:?}", backoff);
+    }
+    
+    #[test]
+    fn survives_non_finite_multipliers() {
+        let max = Duration::from_secs(10);
+        assert_eq!(policy(Duration::from_secs(1), f64::NAN, max).backoff(2), max);
+        assert_eq!(policy(Duration::ZERO, f64::INFINITY, max).backoff(2), max);
+        assert_eq!(policy(Duration::from_secs(1), f64::INFINITY, max).backoff(2), max);
+        assert_eq!(policy(Duration::from_secs(1), f64::NEG_INFINITY, max).backoff(2), Duration::ZERO);
+        assert_eq!(policy(Duration::from_secs(1), -2.0, max).backoff(2), Duration::ZERO);
+    }
+    
+    #[test]
+    fn survives_unbounded_max_backoff() {
+        let policy = policy(Duration::from_secs(1), 1e300, Duration::MAX);
+        assert_eq!(policy.backoff(3), Duration::MAX);
+        
+        let jittered = RetryPolicy { jitter: true, ..policy }.backoff(3);
+        assert!(jittered >= Duration::MAX / 4, "{:?}", jittered);
+    }
+}
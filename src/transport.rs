@@ -0,0 +1,165 @@
+//! The HTTP layer used by [`NwsClient`](crate::NwsClient) and, with the `async` feature, `NwsAsyncClient`.
+//!
+//! By default requests are sent with `reqwest`. Any other [`Transport`] can be plugged in with
+//! [`NwsClient::with_transport`](crate::NwsClient::with_transport), e.g. a [`MemoryTransport`] replaying canned
+//! responses in tests. The async client takes an `AsyncTransport` instead, which `MemoryTransport` implements too.
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+#[cfg(feature = "async")]
+use std::future::{ready, Future};
+#[cfg(feature = "async")]
+use std::pin::Pin;
+use std::sync::{Arc, RwLock};
+use reqwest::blocking::{Client as ReqClient, ClientBuilder};
+use reqwest::header::HeaderMap;
+use crate::Result;
+
+pub use crate::request::{ApiRequest, ApiResponse, Service};
+
+
+/// Sends a single GET request and returns the response, whatever its status.
+pub trait Transport: Debug + Send + Sync {
+    fn send(&self, request: &ApiRequest) -> Result<ApiResponse>;
+}
+impl<T: Transport + ?Sized> Transport for Arc<T> {
+    fn send(&self, request: &ApiRequest) -> Result<ApiResponse> {
+        (**self).send(request)
+    }
+}
+
+/// Transport sending requests over the network with a blocking `reqwest` client.
+#[derive(Clone, Debug)]
+pub struct ReqwestTransport {
+    pub req: ReqClient,
+}
+impl ReqwestTransport {
+    pub fn new(user_agent: &str) -> Result<Self> {
+        Ok(Self { req: ClientBuilder::new().user_agent(user_agent).build()? })
+    }
+}
+impl From<ReqClient> for ReqwestTransport {
+    fn from(req: ReqClient) -> Self {
+        Self { req }
+    }
+}
+impl Transport for ReqwestTransport {
+    fn send(&self, request: &ApiRequest) -> Result<ApiResponse> {
+        let mut req = self.req.get(&request.url).query(&request.params);
+        for (name, value) in &request.headers {
+            req = req.header(*name, value);
+        }
+        let resp = req.send()?;
+        
+        let status = resp.status().as_u16();
+        let headers = header_pairs(resp.headers());
+        
+        Ok(ApiResponse { status, headers, body: resp.bytes()?.to_vec() })
+    }
+}
+
+/// The response future of an [`AsyncTransport`].
+#[cfg(feature = "async")]
+pub type SendFuture<'a> = Pin<Box<dyn Future<Output = Result<ApiResponse>> + Send + 'a>>;
+
+/// Sends a single GET request without blocking and returns the response, whatever its status.
+#[cfg(feature = "async")]
+pub trait AsyncTransport: Debug + Send + Sync {
+    fn send<'a>(&'a self, request: &'a ApiRequest) -> SendFuture<'a>;
+}
+#[cfg(feature = "async")]
+impl<T: AsyncTransport + ?Sized> AsyncTransport for Arc<T> {
+    fn send<'a>(&'a self, request: &'a ApiRequest) -> SendFuture<'a> {
+        (**self).send(request)
+    }
+}
+
+/// Transport sending requests over the network with an async `reqwest` client.
+#[cfg(feature = "async")]
+#[derive(Clone, Debug)]
+pub struct ReqwestAsyncTransport {
+    pub req: reqwest::Client,
+}
+#[cfg(feature = "async")]
+impl ReqwestAsyncTransport {
+    pub fn new(user_agent: &str) -> Result<Self> {
+        Ok(Self { req: reqwest::ClientBuilder::new().user_agent(user_agent).build()? })
+    }
+}
+#[cfg(feature = "async")]
+impl From<reqwest::Client> for ReqwestAsyncTransport {
+    fn from(req: reqwest::Client) -> Self {
+        Self { req }
+    }
+}
+#[cfg(feature = "async")]
+impl AsyncTransport for ReqwestAsyncTransport {
+    fn send<'a>(&'a self, request: &'a ApiRequest) -> SendFuture<'a> {
+        Box::pin(async move {
+            let mut req = self.req.get(&request.url).query(&request.params);
+            for (name, value) in &request.headers {
+                req = req.header(*name, value);
+            }
+            let resp = req.send().await?;
+            
+            let status = resp.status().as_u16();
+            let headers = header_pairs(resp.headers());
+            
+            Ok(ApiResponse { status, headers, body: resp.bytes().await?.to_vec() })
+        })
+    }
+}
+
+/// Headers of a `reqwest` response, skipping any whose value isn't valid text.
+pub(crate) fn header_pairs(headers: &HeaderMap) -> Vec<(String, String)> {
+    headers.iter()
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_owned())))
+        .collect()
+}
+
+/// Transport answering from a fixed set of responses, keyed by the full request URL including the query string.
+/// 
+/// Requests without a registered response get an empty `404`. Every request is recorded, so tests can check what
+/// was sent.
+#[derive(Debug, Default)]
+pub struct MemoryTransport {
+    responses: HashMap<String, ApiResponse>,
+    requests: RwLock<Vec<ApiRequest>>,
+}
+impl MemoryTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Registers the response for a URL, e.g. `https://api.weather.gov/alerts/active?area=KS`.
+    pub fn with_response(mut self, url: impl Into<String>, response: ApiResponse) -> Self {
+        self.responses.insert(url.into(), response);
+        self
+    }
+    
+    /// Registers a `200` JSON-LD response for a URL.
+    pub fn with_json(self, url: impl Into<String>, body: impl Into<Vec<u8>>) -> Self {
+        self.with_response(url, ApiResponse::new(200, body).with_header("Content-Type", "application/ld+json"))
+    }
+    
+    /// Every request sent so far, oldest first.
+    pub fn requests(&self) -> Vec<ApiRequest> {
+        self.requests.read().map(|requests| requests.clone()).unwrap_or_default()
+    }
+}
+impl Transport for MemoryTransport {
+    fn send(&self, request: &ApiRequest) -> Result<ApiResponse> {
+        if let Ok(mut requests) = self.requests.write() {
+            requests.push(request.clone());
+        }
+        let url = request.full_url().map(String::from).unwrap_or_else(|| request.url.clone());
+        
+        Ok(self.responses.get(&url).cloned().unwrap_or_else(|| ApiResponse::new(404, Vec::new())))
+    }
+}
+#[cfg(feature = "async")]
+impl AsyncTransport for MemoryTransport {
+    fn send<'a>(&'a self, request: &'a ApiRequest) -> SendFuture<'a> {
+        Box::pin(ready(Transport::send(self, request)))
+    }
+}
@@ -0,0 +1,266 @@
+//! Units of measure of quantitative values, and conversions between them.
+
+use std::fmt::{Display, Formatter};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+
+/// Unit of measure of a quantitative value, parsed from the WMO/UCUM codes used by the API, e.g. `wmoUnit:degC`.
+///
+/// Codes not known to this crate are kept in `Other`, verbatim.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Unit {
+    DegC,
+    DegF,
+    Kelvin,
+    MetersPerSecond,
+    KilometersPerHour,
+    MilesPerHour,
+    Knots,
+    Pascal,
+    Hectopascal,
+    InchesOfMercury,
+    Meter,
+    Kilometer,
+    Centimeter,
+    Millimeter,
+    Inch,
+    Foot,
+    Mile,
+    Percent,
+    /// Angle in degrees, e.g. a wind direction.
+    DegreeAngle,
+    Other(String),
+}
+impl Unit {
+    /// Parses a unit code, with or without a prefix such as `wmoUnit:`, `nwsUnit:` or `unit:`.
+    pub fn from_code(code: &str) -> Self {
+        let bare = code.split_once(':').map_or(code, |(_, bare)| bare);
+        
+        use Unit::*;
+        match bare {
+            "degC" | "Cel" => DegC,
+            "degF" | "[degF]" => DegF,
+            "K" => Kelvin,
+            "m_s-1" | "m/s" => MetersPerSecond,
+            "km_h-1" | "km/h" => KilometersPerHour,
+            "mi_h-1" | "[mi_i]/h" | "[mph]" => MilesPerHour,
+            "kt" | "[kn_i]" => Knots,
+            "Pa" => Pascal,
+            "hPa" => Hectopascal,
+            "[in_i'Hg]" | "inHg" => InchesOfMercury,
+            "m" => Meter,
+            "km" => Kilometer,
+            "cm" => Centimeter,
+            "mm" => Millimeter,
+            "in" | "[in_i]" => Inch,
+            "ft" | "[ft_i]" => Foot,
+            "mi" | "[mi_i]" => Mile,
+            "percent" | "%" => Percent,
+            "degree_(angle)" | "deg" => DegreeAngle,
+            _ => Other(code.to_owned()),
+        }
+    }
+    
+    /// The code as the API writes it.
+    pub fn code(&self) -> String {
+        use Unit::*;
+        let code = match self {
+            DegC => "degC",
+            DegF => "degF",
+            Kelvin => "K",
+            MetersPerSecond => "m_s-1",
+            KilometersPerHour => "km_h-1",
+            MilesPerHour => "mi_h-1",
+            Knots => "kt",
+            Pascal => "Pa",
+            Hectopascal => "hPa",
+            InchesOfMercury => "[in_i'Hg]",
+            Meter => "m",
+            Kilometer => "km",
+            Centimeter => "cm",
+            Millimeter => "mm",
+            Inch => "in",
+            Foot => "ft",
+            Mile => "mi",
+            Percent => "percent",
+            DegreeAngle => "degree_(angle)",
+            Other(code) => return code.clone(),
+        };
+        
+        format!("wmoUnit:{}", code)
+    }
+    
+    /// Converts `value` from this unit to `to`. `None` if the units measure different things, or either is unknown.
+    pub fn convert(&self, value: f64, to: &Unit) -> Option<f64> {
+        let (from_dimension, from_scale, from_offset) = self.linear()?;
+        let (to_dimension, to_scale, to_offset) = to.linear()?;
+        if from_dimension != to_dimension { return None; }
+        
+        Some((value * from_scale + from_offset - to_offset) / to_scale)
+    }
+    
+    /// The metric unit values in this unit are converted to by `to_si`: °C, m/s, Pa, and mm/m/km for lengths.
+    pub fn si(&self) -> Unit {
+        use Unit::*;
+        match self {
+            DegF | Kelvin => DegC,
+            KilometersPerHour | MilesPerHour | Knots => MetersPerSecond,
+            Hectopascal | InchesOfMercury => Pascal,
+            Inch => Millimeter,
+            Foot => Meter,
+            Mile => Kilometer,
+            unit => unit.clone(),
+        }
+    }
+    
+    /// The US customary unit values in this unit are converted to by `to_us`: °F, mph, inHg, and in/ft/mi for lengths.
+    pub fn us(&self) -> Unit {
+        use Unit::*;
+        match self {
+            DegC | Kelvin => DegF,
+            MetersPerSecond | KilometersPerHour | Knots => MilesPerHour,
+            Pascal | Hectopascal => InchesOfMercury,
+            Millimeter | Centimeter => Inch,
+            Meter => Foot,
+            Kilometer => Mile,
+            unit => unit.clone(),
+        }
+    }
+    
+    /// What the unit measures, and how to turn a value into that dimension's base unit: `value * scale + offset`.
+    fn linear(&self) -> Option<(Dimension, f64, f64)> {
+        use Unit::*;
+        use Dimension::*;
+        Some(match self {
+            Kelvin => (Temperature, 1.0, 0.0),
+            DegC => (Temperature, 1.0, 273.15),
+            DegF => (Temperature, 5.0 / 9.0, 273.15 - 32.0 * 5.0 / 9.0),
+            MetersPerSecond => (Speed, 1.0, 0.0),
+            KilometersPerHour => (Speed, 1.0 / 3.6, 0.0),
+            MilesPerHour => (Speed, 0.44704, 0.0),
+            Knots => (Speed, 1852.0 / 3600.0, 0.0),
+            Pascal => (Pressure, 1.0, 0.0),
+            Hectopascal => (Pressure, 100.0, 0.0),
+            InchesOfMercury => (Pressure, 3386.389, 0.0),
+            Meter => (Length, 1.0, 0.0),
+            Kilometer => (Length, 1000.0, 0.0),
+            Centimeter => (Length, 0.01, 0.0),
+            Millimeter => (Length, 0.001, 0.0),
+            Inch => (Length, 0.0254, 0.0),
+            Foot => (Length, 0.3048, 0.0),
+            Mile => (Length, 1609.344, 0.0),
+            Percent => (Ratio, 1.0, 0.0),
+            DegreeAngle => (Angle, 1.0, 0.0),
+            Other(_) => return None,
+        })
+    }
+}
+impl Display for Unit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+impl Serialize for Unit {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        serializer.serialize_str(&self.code())
+    }
+}
+impl<'de> Deserialize<'de> for Unit {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+        Ok(Unit::from_code(&String::deserialize(deserializer)?))
+    }
+}
+
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Dimension {
+    Temperature,
+    Speed,
+    Pressure,
+    Length,
+    Ratio,
+    Angle,
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::endpoints::QuantitativeValue;
+    use super::*;
+    
+    fn assert_close(actual: Option<f64>, expected: f64) {
+        let actual = actual.unwrap();
+        assert!((actual - expected).abs() < 1e-6, "{} != {}", actual, expected);
+    }
+    
+    #[test]
+    fn parses_prefixed_codes() {
+        assert_eq!(Unit::from_code("wmoUnit:degC"), Unit::DegC);
+        assert_eq!(Unit::from_code("unit:degF"), Unit::DegF);
+        assert_eq!(Unit::from_code("nwsUnit:[mi_i]/h"), Unit::MilesPerHour);
+        assert_eq!(Unit::from_code("wmoUnit:km_h-1"), Unit::KilometersPerHour);
+        assert_eq!(Unit::from_code("wmoUnit:percent"), Unit::Percent);
+        assert_eq!(Unit::from_code("wmoUnit:degree_(angle)"), Unit::DegreeAngle);
+        assert_eq!(Unit::from_code("mm"), Unit::Millimeter);
+    }
+    
+    #[test]
+    fn keeps_unknown_codes() {
+        let unit = Unit::from_code("wmoUnit:kg_m-2");
+        assert_eq!(unit, Unit::Other("wmoUnit:kg_m-2".to_owned()));
+        assert_eq!(unit.code(), "wmoUnit:kg_m-2");
+        assert_eq!(serde_json::to_string(&unit).unwrap(), "\"wmoUnit:kg_m-2\"");
+    }
+    
+    #[test]
+    fn round_trips_codes() {
+        let unit: Unit = serde_json::from_str("\"wmoUnit:m_s-1\"").unwrap();
+        assert_eq!(unit, Unit::MetersPerSecond);
+        assert_eq!(serde_json::to_string(&unit).unwrap(), "\"wmoUnit:m_s-1\"");
+        assert_eq!(Unit::from_code(&Unit::InchesOfMercury.code()), Unit::InchesOfMercury);
+    }
+    
+    #[test]
+    fn converts_within_a_dimension() {
+        assert_close(Unit::DegC.convert(100.0, &Unit::DegF), 212.0);
+        assert_close(Unit::DegF.convert(32.0, &Unit::DegC), 0.0);
+        assert_close(Unit::Kelvin.convert(0.0, &Unit::DegC), -273.15);
+        assert_close(Unit::KilometersPerHour.convert(36.0, &Unit::MetersPerSecond), 10.0);
+        assert_close(Unit::Knots.convert(1.0, &Unit::KilometersPerHour), 1.852);
+        assert_close(Unit::Pascal.convert(101_325.0, &Unit::Hectopascal), 1013.25);
+        assert_close(Unit::Millimeter.convert(25.4, &Unit::Inch), 1.0);
+        assert_close(Unit::Mile.convert(1.0, &Unit::Meter), 1609.344);
+    }
+    
+    #[test]
+    fn rejects_incompatible_units() {
+        assert_eq!(Unit::DegC.convert(1.0, &Unit::Meter), None);
+        assert_eq!(Unit::Percent.convert(1.0, &Unit::DegreeAngle), None);
+        assert_eq!(Unit::Other("wmoUnit:kg_m-2".to_owned()).convert(1.0, &Unit::Millimeter), None);
+    }
+    
+    #[test]
+    fn converts_quantitative_values() {
+        let value = QuantitativeValue {
+            unit_code: Some(Unit::DegC),
+            value: Some(20.0),
+            max_value: Some(25.0),
+            min_value: None,
+            quality_control: Some("V".to_owned()),
+        };
+        
+        let us = value.to_us();
+        assert_eq!(us.unit_code, Some(Unit::DegF));
+        assert_close(us.value, 68.0);
+        assert_close(us.max_value, 77.0);
+        assert_eq!(us.min_value, None);
+        assert_close(value.value_in(Unit::Kelvin), 293.15);
+        
+        assert!(value.to_unit(Unit::Meter).is_none());
+        assert_eq!(value.value_in(Unit::Meter), None);
+        
+        let unknown = QuantitativeValue { unit_code: Some(Unit::from_code("wmoUnit:kg_m-2")), ..value };
+        assert!(unknown.to_unit(Unit::Millimeter).is_none());
+        assert_eq!(unknown.to_si().unit_code, unknown.unit_code);
+    }
+}
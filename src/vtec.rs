@@ -0,0 +1,587 @@
+//! Parsing of P-VTEC strings and UGC codes, as found in alert parameters, text products and gridpoint hazards.
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ProductClass {
+    Operational,
+    Test,
+    Experimental,
+    ExperimentalInOperational,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    New,
+    Continued,
+    Extended,
+    ExtendedInArea,
+    ExtendedInTimeAndArea,
+    Upgraded,
+    Cancelled,
+    Expired,
+    Corrected,
+    Routine,
+}
+impl Display for Action {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        use Action::*;
+        write!(f, "{}", match self {
+            New => "NEW",
+            Continued => "CON",
+            Extended => "EXT",
+            ExtendedInArea => "EXA",
+            ExtendedInTimeAndArea => "EXB",
+            Upgraded => "UPG",
+            Cancelled => "CAN",
+            Expired => "EXP",
+            Corrected => "COR",
+            Routine => "ROU",
+        })
+    }
+}
+impl FromStr for Action {
+    type Err = ();
+    
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use Action::*;
+        match s {
+            "NEW" => Ok(New),
+            "CON" => Ok(Continued),
+            "EXT" => Ok(Extended),
+            "EXA" => Ok(ExtendedInArea),
+            "EXB" => Ok(ExtendedInTimeAndArea),
+            "UPG" => Ok(Upgraded),
+            "CAN" => Ok(Cancelled),
+            "EXP" => Ok(Expired),
+            "COR" => Ok(Corrected),
+            "ROU" => Ok(Routine),
+            _ => Err(())
+        }
+    }
+}
+
+/// The two-letter VTEC phenomena code. Codes not known to this crate are kept in `Other`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Phenomenon {
+    Ashfall,
+    AirStagnation,
+    BeachHazard,
+    BriskWind,
+    Blizzard,
+    CoastalFlood,
+    DebrisFlow,
+    DustStorm,
+    BlowingDust,
+    ExtremeCold,
+    ExcessiveHeat,
+    ExtremeHeat,
+    ArealFlood,
+    FlashFlood,
+    DenseFog,
+    Flood,
+    Frost,
+    FireWeather,
+    Freeze,
+    Gale,
+    HurricaneForceWind,
+    Heat,
+    Hurricane,
+    HighWind,
+    Hydrologic,
+    HardFreeze,
+    IceStorm,
+    LakeEffectSnow,
+    LowWater,
+    LakeshoreFlood,
+    LakeWind,
+    Marine,
+    MarineDenseFog,
+    MarineAshfall,
+    MarineDenseSmoke,
+    SmallCraftRoughBar,
+    RipCurrentRisk,
+    SmallCraft,
+    HazardousSeas,
+    SmallCraftWinds,
+    DenseSmoke,
+    SnowSquall,
+    Storm,
+    StormSurge,
+    HighSurf,
+    SevereThunderstorm,
+    SmallCraftHazardousSeas,
+    Tornado,
+    TropicalStorm,
+    Tsunami,
+    Typhoon,
+    HeavyFreezingSpray,
+    WindChill,
+    Wind,
+    WinterStorm,
+    WinterWeather,
+    FreezingFog,
+    FreezingRain,
+    Other(String),
+}
+impl Phenomenon {
+    pub fn from_code(code: &str) -> Self {
+        use Phenomenon::*;
+        match code {
+            "AF" => Ashfall,
+            "AS" => AirStagnation,
+            "BH" => BeachHazard,
+            "BW" => BriskWind,
+            "BZ" => Blizzard,
+            "CF" => CoastalFlood,
+            "DF" => DebrisFlow,
+            "DS" => DustStorm,
+            "DU" => BlowingDust,
+            "EC" => ExtremeCold,
+            "EH" => ExcessiveHeat,
+            "XH" => ExtremeHeat,
+            "FA" => ArealFlood,
+            "FF" => FlashFlood,
+            "FG" => DenseFog,
+            "FL" => Flood,
+            "FR" => Frost,
+            "FW" => FireWeather,
+            "FZ" => Freeze,
+            "GL" => Gale,
+            "HF" => HurricaneForceWind,
+            "HT" => Heat,
+            "HU" => Hurricane,
+            "HW" => HighWind,
+            "HY" => Hydrologic,
+            "HZ" => HardFreeze,
+            "IS" => IceStorm,
+            "LE" => LakeEffectSnow,
+            "LO" => LowWater,
+            "LS" => LakeshoreFlood,
+            "LW" => LakeWind,
+            "MA" => Marine,
+            "MF" => MarineDenseFog,
+            "MH" => MarineAshfall,
+            "MS" => MarineDenseSmoke,
+            "RB" => SmallCraftRoughBar,
+            "RP" => RipCurrentRisk,
+            "SC" => SmallCraft,
+            "SE" => HazardousSeas,
+            "SI" => SmallCraftWinds,
+            "SM" => DenseSmoke,
+            "SQ" => SnowSquall,
+            "SR" => Storm,
+            "SS" => StormSurge,
+            "SU" => HighSurf,
+            "SV" => SevereThunderstorm,
+            "SW" => SmallCraftHazardousSeas,
+            "TO" => Tornado,
+            "TR" => TropicalStorm,
+            "TS" => Tsunami,
+            "TY" => Typhoon,
+            "UP" => HeavyFreezingSpray,
+            "WC" => WindChill,
+            "WI" => Wind,
+            "WS" => WinterStorm,
+            "WW" => WinterWeather,
+            "ZF" => FreezingFog,
+            "ZR" => FreezingRain,
+            _ => Other(code.to_owned()),
+        }
+    }
+    
+    pub fn code(&self) -> &str {
+        use Phenomenon::*;
+        match self {
+            Ashfall => "AF",
+            AirStagnation => "AS",
+            BeachHazard => "BH",
+            BriskWind => "BW",
+            Blizzard => "BZ",
+            CoastalFlood => "CF",
+            DebrisFlow => "DF",
+            DustStorm => "DS",
+            BlowingDust => "DU",
+            ExtremeCold => "EC",
+            ExcessiveHeat => "EH",
+            ExtremeHeat => "XH",
+            ArealFlood => "FA",
+            FlashFlood => "FF",
+            DenseFog => "FG",
+            Flood => "FL",
+            Frost => "FR",
+            FireWeather => "FW",
+            Freeze => "FZ",
+            Gale => "GL",
+            HurricaneForceWind => "HF",
+            Heat => "HT",
+            Hurricane => "HU",
+            HighWind => "HW",
+            Hydrologic => "HY",
+            HardFreeze => "HZ",
+            IceStorm => "IS",
+            LakeEffectSnow => "LE",
+            LowWater => "LO",
+            LakeshoreFlood => "LS",
+            LakeWind => "LW",
+            Marine => "MA",
+            MarineDenseFog => "MF",
+            MarineAshfall => "MH",
+            MarineDenseSmoke => "MS",
+            SmallCraftRoughBar => "RB",
+            RipCurrentRisk => "RP",
+            SmallCraft => "SC",
+            HazardousSeas => "SE",
+            SmallCraftWinds => "SI",
+            DenseSmoke => "SM",
+            SnowSquall => "SQ",
+            Storm => "SR",
+            StormSurge => "SS",
+            HighSurf => "SU",
+            SevereThunderstorm => "SV",
+            SmallCraftHazardousSeas => "SW",
+            Tornado => "TO",
+            TropicalStorm => "TR",
+            Tsunami => "TS",
+            Typhoon => "TY",
+            HeavyFreezingSpray => "UP",
+            WindChill => "WC",
+            Wind => "WI",
+            WinterStorm => "WS",
+            WinterWeather => "WW",
+            FreezingFog => "ZF",
+            FreezingRain => "ZR",
+            Other(code) => code,
+        }
+    }
+}
+impl Display for Phenomenon {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+impl Serialize for Phenomenon {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        serializer.serialize_str(self.code())
+    }
+}
+impl<'de> Deserialize<'de> for Phenomenon {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+        Ok(Phenomenon::from_code(&String::deserialize(deserializer)?))
+    }
+}
+
+/// The one-letter VTEC significance code. Codes not known to this crate are kept in `Other`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Significance {
+    Warning,
+    Watch,
+    Advisory,
+    Statement,
+    Forecast,
+    Outlook,
+    Synopsis,
+    Other(String),
+}
+impl Significance {
+    pub fn from_code(code: &str) -> Self {
+        use Significance::*;
+        match code {
+            "W" => Warning,
+            "A" => Watch,
+            "Y" => Advisory,
+            "S" => Statement,
+            "F" => Forecast,
+            "O" => Outlook,
+            "N" => Synopsis,
+            _ => Other(code.to_owned()),
+        }
+    }
+    
+    pub fn code(&self) -> &str {
+        use Significance::*;
+        match self {
+            Warning => "W",
+            Watch => "A",
+            Advisory => "Y",
+            Statement => "S",
+            Forecast => "F",
+            Outlook => "O",
+            Synopsis => "N",
+            Other(code) => code,
+        }
+    }
+}
+impl Display for Significance {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+impl Serialize for Significance {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        serializer.serialize_str(self.code())
+    }
+}
+impl<'de> Deserialize<'de> for Significance {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+        Ok(Significance::from_code(&String::deserialize(deserializer)?))
+    }
+}
+
+
+/// A primary VTEC string, e.g. `/O.NEW.KLWX.WS.W.0003.220101T1200Z-220102T0000Z/`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Vtec {
+    pub class: ProductClass,
+    pub action: Action,
+    /// Four-letter issuing office, e.g. `KLWX`.
+    pub office: String,
+    pub phenomenon: Phenomenon,
+    pub significance: Significance,
+    /// Event tracking number.
+    pub etn: u32,
+    /// `None` when the event has already begun (`000000T0000Z`).
+    pub begin: Option<DateTime<Utc>>,
+    /// `None` when the event has no defined end (`000000T0000Z`).
+    pub end: Option<DateTime<Utc>>,
+}
+impl Vtec {
+    pub fn parse(vtec: impl AsRef<str>) -> Option<Self> {
+        let vtec = vtec.as_ref().trim().strip_prefix('/')?.strip_suffix('/')?;
+        let parts: Vec<&str> = vtec.split('.').collect();
+        let [class, action, office, phenomenon, significance, etn, times] = parts.as_slice() else {
+            return None;
+        };
+        let (begin, end) = times.split_once('-')?;
+        
+        use ProductClass::*;
+        let class = match *class {
+            "O" => Operational,
+            "T" => Test,
+            "E" => Experimental,
+            "X" => ExperimentalInOperational,
+            _ => return None
+        };
+        if office.len() != 4 || phenomenon.len() != 2 || significance.len() != 1 || etn.len() != 4 {
+            return None;
+        }
+        
+        Some(Vtec {
+            class,
+            action: action.parse().ok()?,
+            office: office.to_string(),
+            phenomenon: Phenomenon::from_code(phenomenon),
+            significance: Significance::from_code(significance),
+            etn: etn.parse().ok()?,
+            begin: parse_vtec_time(begin)?,
+            end: parse_vtec_time(end)?,
+        })
+    }
+}
+
+/// Returns `Some(None)` for the all-zero placeholder time, and `None` if the time is malformed.
+fn parse_vtec_time(time: &str) -> Option<Option<DateTime<Utc>>> {
+    if time == "000000T0000Z" {
+        return Some(None);
+    }
+    
+    NaiveDateTime::parse_from_str(time, "%y%m%dT%H%MZ").ok()
+        .map(|time| Some(DateTime::from_utc(time, Utc)))
+}
+
+
+/// A Universal Geographic Code string, e.g. `MDZ003-VAZ052>055-057-020000-`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ugc {
+    /// Every zone or county code covered, with ranges expanded, e.g. `VAZ052`, `VAZ053`.
+    pub codes: Vec<String>,
+    /// Product purge time as day of month, hour and minute (UTC), if present.
+    pub purge_time: Option<(u32, u32, u32)>,
+}
+impl Ugc {
+    /// Parses a UGC string. Continuation lines may be joined with or without whitespace between them.
+    pub fn parse(ugc: impl AsRef<str>) -> Option<Self> {
+        let ugc: String = ugc.as_ref().split_whitespace().collect();
+        let mut codes = vec![];
+        let mut purge_time = None;
+        let mut prefix: Option<&str> = None;
+        
+        for token in ugc.split('-').filter(|token| !token.is_empty()) {
+            if token.len() == 6 && token.chars().all(|c| c.is_ascii_digit()) {
+                purge_time = Some((token[0..2].parse().ok()?, token[2..4].parse().ok()?, token[4..6].parse().ok()?));
+                continue;
+            }
+            
+            let numbers = match token.get(..3) {
+                Some(head) if head.chars().all(|c| c.is_ascii_alphabetic()) => {
+                    prefix = Some(head);
+                    &token[3..]
+                },
+                _ => token,
+            };
+            let prefix = prefix?;
+            
+            let (first, last) = match numbers.split_once('>') {
+                Some((first, last)) => (first, last.trim_start_matches(prefix)),
+                None => (numbers, numbers),
+            };
+            let (first, last) = (parse_ugc_number(first)?, parse_ugc_number(last)?);
+            if first > last || codes.len() + (last - first + 1) as usize > MAX_UGC_CODES {
+                return None;
+            }
+            
+            codes.extend((first..=last).map(|number| format!("{}{:03}", prefix, number)));
+        }
+        
+        Some(Ugc {
+            codes,
+            purge_time,
+        })
+    }
+}
+
+/// The most codes a UGC string may expand to. Real products list at most a few thousand, so anything beyond this is
+/// rejected rather than allocated.
+const MAX_UGC_CODES: usize = 10_000;
+
+/// A zone or county number, which is always exactly 3 digits.
+fn parse_ugc_number(number: &str) -> Option<u32> {
+    if number.len() != 3 || !number.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    
+    number.parse().ok()
+}
+
+/// Expands a UGC string such as `VAZ052>055-057` into its individual codes. Returns `None` if the string is malformed.
+pub fn expand_ugc(ugc: impl AsRef<str>) -> Option<Vec<String>> {
+    Ugc::parse(ugc).map(|ugc| ugc.codes)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use crate::endpoints::gridpoints::Hazards;
+    use super::*;
+    
+    #[test]
+    fn parses_vtec() {
+        let vtec = Vtec::parse("/O.NEW.KLWX.WS.W.0003.220101T1200Z-220102T0000Z/").unwrap();
+        
+        assert_eq!(vtec, Vtec {
+            class: ProductClass::Operational,
+            action: Action::New,
+            office: "KLWX".to_owned(),
+            phenomenon: Phenomenon::WinterStorm,
+            significance: Significance::Warning,
+            etn: 3,
+            begin: Some(Utc.ymd(2022, 1, 1).and_hms(12, 0, 0)),
+            end: Some(Utc.ymd(2022, 1, 2).and_hms(0, 0, 0)),
+        });
+    }
+    
+    #[test]
+    fn parses_open_ended_times() {
+        let vtec = Vtec::parse("/O.CON.KOUN.TO.A.0123.000000T0000Z-220502T0300Z/").unwrap();
+        assert_eq!(vtec.action, Action::Continued);
+        assert_eq!((vtec.phenomenon, vtec.significance), (Phenomenon::Tornado, Significance::Watch));
+        assert_eq!(vtec.begin, None);
+        assert_eq!(vtec.end, Some(Utc.ymd(2022, 5, 2).and_hms(3, 0, 0)));
+        
+        let vtec = Vtec::parse("  /T.EXT.KBOU.FW.Y.0010.220501T1800Z-000000T0000Z/\n").unwrap();
+        assert_eq!(vtec.class, ProductClass::Test);
+        assert_eq!(vtec.end, None);
+    }
+    
+    #[test]
+    fn keeps_unknown_codes() {
+        let vtec = Vtec::parse("/O.NEW.KLWX.QQ.Z.0001.220101T1200Z-220102T0000Z/").unwrap();
+        
+        assert_eq!(vtec.phenomenon, Phenomenon::Other("QQ".to_owned()));
+        assert_eq!(vtec.significance, Significance::Other("Z".to_owned()));
+        assert_eq!(vtec.phenomenon.to_string(), "QQ");
+    }
+    
+    #[test]
+    fn rejects_malformed_vtec() {
+        for vtec in [
+            "",
+            "O.NEW.KLWX.WS.W.0003.220101T1200Z-220102T0000Z",
+            "/O.NEW.KLWX.WS.W.0003/",
+            "/Q.NEW.KLWX.WS.W.0003.220101T1200Z-220102T0000Z/",
+            "/O.XYZ.KLWX.WS.W.0003.220101T1200Z-220102T0000Z/",
+            "/O.NEW.LWX.WS.W.0003.220101T1200Z-220102T0000Z/",
+            "/O.NEW.KLWX.WS.W.003A.220101T1200Z-220102T0000Z/",
+            "/O.NEW.KLWX.WS.W.0003.220101T1260Z-220102T0000Z/",
+            "/O.NEW.KLWX.WS.W.0003.220101T1200Z/",
+            "/O.NEW.KLWÜ.WS.W.0003.220101T1200Z-220102T0000Z/",
+        ] {
+            assert_eq!(Vtec::parse(vtec), None, "{:?}", vtec);
+        }
+    }
+    
+    #[test]
+    fn parses_multi_state_ugc() {
+        let ugc = Ugc::parse("MDZ003-VAZ052>055-\n057-020000-").unwrap();
+        
+        assert_eq!(ugc.codes, ["MDZ003", "VAZ052", "VAZ053", "VAZ054", "VAZ055", "VAZ057"]);
+        assert_eq!(ugc.purge_time, Some((2, 0, 0)));
+    }
+    
+    #[test]
+    fn parses_county_ugc_and_prefixed_ranges() {
+        assert_eq!(expand_ugc("OKC017-027-109-"), Some(vec!["OKC017".to_owned(), "OKC027".to_owned(), "OKC109".to_owned()]));
+        assert_eq!(expand_ugc("VAZ052>VAZ054"), Some(vec!["VAZ052".to_owned(), "VAZ053".to_owned(), "VAZ054".to_owned()]));
+        assert_eq!(Ugc::parse("DCZ001-").unwrap().purge_time, None);
+    }
+    
+    #[test]
+    fn rejects_malformed_ugc() {
+        for ugc in ["052-057-", "VAZ055>052-", "VAZ05X-", "VAZ052>-", "ÄÄZ052-", "VAZ05Ä-"] {
+            assert_eq!(Ugc::parse(ugc), None, "{:?}", ugc);
+        }
+    }
+    
+    #[test]
+    fn requires_three_digit_numbers() {
+        for ugc in ["VAZ52-", "VAZ0052-", "VAZ1000-", "VAZ001>4000000000-", "VAZ001>1000-", "VAZ001>ALZ003-", "VAZ+01-"] {
+            assert_eq!(Ugc::parse(ugc), None, "{:?}", ugc);
+        }
+    }
+    
+    #[test]
+    fn rejects_reversed_ranges() {
+        assert_eq!(Ugc::parse("VAZ055>052-"), None);
+        assert_eq!(Ugc::parse("VAZ052>052-").unwrap().codes, ["VAZ052"]);
+    }
+    
+    #[test]
+    fn rejects_absurd_expansions() {
+        let full = Ugc::parse("VAZ000>999-").unwrap();
+        assert_eq!(full.codes.len(), 1000);
+        assert_eq!(full.codes.last().map(String::as_str), Some("VAZ999"));
+        
+        let ugc = "VAZ000>999-".repeat(11);
+        assert_eq!(Ugc::parse(ugc), None);
+    }
+    
+    #[test]
+    fn gridpoint_hazards_keep_unknown_codes() {
+        let hazards: Hazards = serde_json::from_str(r#"{"values": [
+            {"validTime": "2022-05-01T12:00:00+00:00/PT6H", "value": [
+                {"phenomenon": "WS", "significance": "A", "event_number": 3},
+                {"phenomenon": "QQ", "significance": "Z", "event_number": null}
+            ]}
+        ]}"#).unwrap();
+        let value = &hazards.values[0].value;
+        
+        assert_eq!((&value[0].phenomenon, &value[0].significance), (&Phenomenon::WinterStorm, &Significance::Watch));
+        assert_eq!((&value[1].phenomenon, &value[1].significance), (&Phenomenon::Other("QQ".to_owned()), &Significance::Other("Z".to_owned())));
+        assert_eq!(serde_json::to_value(&value[1]).unwrap()["phenomenon"], "QQ");
+    }
+}
@@ -0,0 +1,40 @@
+use chrono::{Duration, FixedOffset, TimeZone, Utc};
+use chrono_tz::Tz;
+use nws_api_rs::endpoints::gridpoints::{Forecast, ValidInterval};
+use nws_api_rs::endpoints::stations::{Observation, ObservationStation};
+
+#[test]
+fn deserializes_observation() {
+    let observation: Observation = serde_json::from_str(include_str!("fixtures/api/observation.json")).unwrap();
+    
+    assert_eq!(observation.timestamp, FixedOffset::west(5 * 3600).ymd(2022, 5, 1).and_hms(13, 52, 0));
+    assert_eq!(observation.timestamp, Utc.ymd(2022, 5, 1).and_hms(18, 52, 0));
+    assert_eq!(observation.temperature.value, Some(26.7));
+    assert_eq!(observation.cloud_layers.len(), 2);
+}
+
+#[test]
+fn deserializes_station() {
+    let station: ObservationStation = serde_json::from_str(include_str!("fixtures/api/station.json")).unwrap();
+    
+    assert_eq!(station.time_zone, Tz::America__Chicago);
+    assert_eq!(station.station_identifier, "KOKC");
+}
+
+#[test]
+fn rejects_unknown_time_zone() {
+    let station = include_str!("fixtures/api/station.json").replace("America/Chicago", "America/Atlantis");
+    
+    assert!(serde_json::from_str::<ObservationStation>(&station).is_err());
+}
+
+#[test]
+fn deserializes_forecast() {
+    let forecast: Forecast = serde_json::from_str(include_str!("fixtures/api/forecast.json")).unwrap();
+    
+    assert_eq!(forecast.valid_times, ValidInterval::new(Utc.ymd(2022, 5, 1).and_hms(12, 0, 0), Duration::days(7) + Duration::hours(13)));
+    assert_eq!(forecast.periods[0].temperature, 81.0);
+    
+    let round_trip: Forecast = serde_json::from_value(serde_json::to_value(&forecast).unwrap()).unwrap();
+    assert_eq!(round_trip.valid_times, forecast.valid_times);
+}
@@ -0,0 +1,112 @@
+use std::sync::Arc;
+use chrono::{TimeZone, Utc};
+use nws_api_rs::{NwsClient, NwsError, ScrapeErrorKind};
+use nws_api_rs::endpoints::radar::{FileSize, RadarType};
+use nws_api_rs::retry::RetryPolicy;
+use nws_api_rs::transport::{ApiResponse, MemoryTransport};
+
+const LISTING_URL: &str = "https://mrms.ncep.noaa.gov/data/RIDGEII/L2/KTLX/BREF_RAW/";
+
+
+fn client(fixture: &str) -> NwsClient {
+    let transport = MemoryTransport::new().with_response(LISTING_URL, ApiResponse::new(200, fixture));
+    
+    NwsClient::new("nws-api-rs tests").unwrap().with_transport(transport)
+}
+
+fn scrape_error(fixture: &str) -> ScrapeErrorKind {
+    match client(fixture).radar("KTLX", RadarType::BrefRaw) {
+        Err(NwsError::Scrape(err)) => {
+            assert_eq!(err.url, LISTING_URL);
+            assert!(err.row.contains("<tr>"), "row should be the offending HTML: {}", err.row);
+            err.kind
+        }
+        other => panic!("expected a scrape error, got {:?}", other),
+    }
+}
+
+
+#[test]
+fn parses_listing() {
+    let files = client(include_str!("fixtures/mrms/listing.html")).radar("KTLX", RadarType::BrefRaw).unwrap();
+    
+    assert_eq!(files.len(), 4);
+    assert_eq!(files[0].url, "https://mrms.ncep.noaa.gov/data/RIDGEII/L2/KTLX/");
+    assert!(files[0].last_modified.is_none());
+    assert!(files[0].size.is_none());
+    
+    assert_eq!(files[1].url, format!("{}KTLX_BREF_RAW_20230417_120312.tif.gz", LISTING_URL));
+    assert_eq!(files[1].last_modified, Some(Utc.ymd(2023, 4, 17).and_hms(12, 4, 0)));
+    assert!(matches!(files[1].size, Some(FileSize::K(size)) if size == 312.0));
+    assert!(matches!(files[2].size, Some(FileSize::M(size)) if size == 1.2));
+    assert!(matches!(files[3].size, Some(FileSize::B(size)) if size == 948.0));
+}
+
+#[test]
+fn follows_mrms_host() {
+    let transport = MemoryTransport::new().with_response(
+        "http://localhost:8080/data/RIDGEII/L2/KTLX/BREF_RAW/",
+        ApiResponse::new(200, include_str!("fixtures/mrms/listing.html")),
+    );
+    let client = NwsClient::new("nws-api-rs tests").unwrap().with_transport(transport).with_mrms_url("http://localhost:8080/");
+    let files = client.radar("KTLX", RadarType::BrefRaw).unwrap();
+    
+    assert_eq!(files[0].url, "http://localhost:8080/data/RIDGEII/L2/KTLX/");
+    assert!(files[1].url.starts_with("http://localhost:8080/data/RIDGEII/L2/KTLX/BREF_RAW/"));
+}
+
+#[test]
+fn rejects_row_with_missing_cell() {
+    let kind = scrape_error(include_str!("fixtures/mrms/missing_cell.html"));
+    
+    assert_eq!(kind, ScrapeErrorKind::MissingCells { expected: 3, found: 2 });
+}
+
+#[test]
+fn rejects_malformed_date() {
+    let kind = scrape_error(include_str!("fixtures/mrms/bad_date.html"));
+    
+    assert_eq!(kind, ScrapeErrorKind::InvalidDate("2023-04-17 12:08".to_owned()));
+}
+
+#[test]
+fn rejects_malformed_size() {
+    let kind = scrape_error(include_str!("fixtures/mrms/bad_size.html"));
+    
+    assert_eq!(kind, ScrapeErrorKind::InvalidSize("1.2 MB".to_owned()));
+}
+
+#[test]
+fn rejects_parent_directory_without_link() {
+    let kind = scrape_error(include_str!("fixtures/mrms/no_parent_link.html"));
+    
+    assert_eq!(kind, ScrapeErrorKind::MissingLink);
+}
+
+#[test]
+fn reports_http_errors() {
+    let result = NwsClient::new("nws-api-rs tests").unwrap().with_transport(MemoryTransport::new()).radar("KTLX", RadarType::BrefRaw);
+    
+    assert!(matches!(result, Err(NwsError::Http { status: 404, .. })));
+}
+
+#[test]
+fn file_size_never_panics() {
+    assert!(matches!(FileSize::new("20G"), Some(FileSize::G(size)) if size == 20.0));
+    for value in ["", "-", "K", "1.2.3K", "12Ä", "ÄK", "lots"] {
+        assert!(FileSize::new(value).is_none(), "{:?}", value);
+    }
+}
+
+#[test]
+fn rebases_onto_mrms_mirror_once() {
+    let transport = Arc::new(MemoryTransport::new());
+    let client = NwsClient::new("nws-api-rs tests").unwrap()
+        .with_transport(transport.clone())
+        .with_retry_policy(RetryPolicy::none())
+        .with_mrms_url("https://mrms.ncep.noaa.gov/mirror");
+    let _ = client.radar("KTLX", RadarType::BrefRaw);
+    
+    let urls: Vec<String> = transport.requests().into_iter().map(|request| request.url).collect();
+    assert_eq!(urls, ["https://mrms.ncep.noaa.gov/mirror/data/RIDGEII/L2/KTLX/BREF_RAW/"]);
+}